@@ -1,12 +1,25 @@
 import { Webview } from "./webview";
 import lib from "./core";
+import type {
+    AppOptions,
+    AutomationOptions,
+    AutomationServer,
+    MessageBoxOptions,
+    NotificationOptions,
+    UrlSchemeOptions,
+    WindowInfo,
+} from "./types";
 import { EventEmitter } from "events";
+import { randomBytes } from "crypto";
+import { resolve } from "path";
 
 export const events = new EventEmitter();
 
 let _boxedIpc: any = null;
-let _waits: (() => void)[] = [];
+let _options: AppOptions = {};
+let _waits: { resolve: () => void; reject: (error: Error) => void }[] = [];
 let _ready = false;
+let _startError: Error | null = null;
 let _start = false;
 
 const listener = (event: string, ...args: any[]) => {
@@ -19,39 +32,133 @@ const listener = (event: string, ...args: any[]) => {
         case "ipc": {
             const [windowId, message] = args;
             const { channel, payload } = JSON.parse(message);
-            const browserWindow = Webview.all.find((b) =>
-                lib.compare_window_id(b.boxedWindowId, windowId)
-            )!;
+            const browserWindow = Webview.fromId(windowId)!;
             browserWindow.ipc.emit(channel, payload);
             break;
         }
+        case "child-ipc": {
+            const [windowId, childId, message] = args;
+            const { channel, payload } = JSON.parse(message);
+            const browserWindow = Webview.fromId(windowId)!;
+            browserWindow.children.get(childId)?.ipc.emit(channel, payload);
+            break;
+        }
         case "close-window": {
             const [windowId] = args;
-            const browserWindow = Webview.all.find((b) =>
-                lib.compare_window_id(b.boxedWindowId, windowId)
-            )!;
+            const browserWindow = Webview.fromId(windowId)!;
 
             browserWindow.close().then(() => {
                 browserWindow.emit("close");
             });
             break;
         }
+        case "window-closed": {
+            const [windowId] = args;
+            const browserWindow = Webview.fromId(windowId);
+            if (browserWindow) {
+                browserWindow._markClosed();
+                browserWindow.emit("close");
+            }
+            break;
+        }
         case "resize-window": {
             const [windowId, width, height] = args;
-            const browserWindow = Webview.all.find((b) =>
-                lib.compare_window_id(b.boxedWindowId, windowId)
-            )!;
+            const browserWindow = Webview.fromId(windowId)!;
             browserWindow.emit("resize", width, height);
             break;
         }
         case "move-window": {
             const [windowId, x, y] = args;
-            const browserWindow = Webview.all.find((b) =>
-                lib.compare_window_id(b.boxedWindowId, windowId)
-            )!;
+            const browserWindow = Webview.fromId(windowId)!;
             browserWindow.emit("move", x, y);
             break;
         }
+        case "file-drop-hovered":
+        case "file-drop-dropped": {
+            const [windowId, paths, x, y] = args;
+            const browserWindow = Webview.fromId(windowId)!;
+            const position = x === null ? null : { x, y };
+            browserWindow.emit(event, paths, position);
+            break;
+        }
+        case "file-drop-cancelled": {
+            const [windowId] = args;
+            const browserWindow = Webview.fromId(windowId)!;
+            browserWindow.emit(event);
+            break;
+        }
+        case "security-violation": {
+            const [windowId, violation, origin, channel] = args;
+            const browserWindow = Webview.fromId(windowId)!;
+            browserWindow.emit(event, violation, origin, channel);
+            break;
+        }
+        case "download-requested": {
+            const [windowId, id, url, suggestedFilename] = args;
+            const browserWindow = Webview.fromId(windowId)!;
+            const handler = browserWindow.options.downloadHandler;
+            Promise.resolve(handler ? handler(url, suggestedFilename) : null)
+                .catch((err) => {
+                    events.emit("error", err);
+                    return null;
+                })
+                .then((path) => {
+                    lib.resolve_download(getBoxedIpc(), id, path, () => {});
+                });
+            break;
+        }
+        case "devtools-opened":
+        case "devtools-closed": {
+            const [windowId] = args;
+            const browserWindow = Webview.fromId(windowId)!;
+            browserWindow.emit(event);
+            break;
+        }
+        case "zoom-changed": {
+            const [windowId, factor] = args;
+            const browserWindow = Webview.fromId(windowId)!;
+            browserWindow.emit(event, factor);
+            break;
+        }
+        case "theme-changed": {
+            const [windowId, theme] = args;
+            const browserWindow = Webview.fromId(windowId)!;
+            browserWindow.emit(event, theme);
+            break;
+        }
+        case "download-started":
+        case "download-progress":
+        case "download-completed": {
+            const [windowId, ...payload] = args;
+            const browserWindow = Webview.fromId(windowId)!;
+            browserWindow.emit(event, ...payload);
+            break;
+        }
+        case "second-instance": {
+            const [argv, cwd] = args;
+            events.emit("second-instance", argv, cwd);
+            break;
+        }
+        case "open-url": {
+            const [url] = args;
+            events.emit("open-url", url);
+            break;
+        }
+        case "notification-click": {
+            const [id] = args;
+            events.emit("notification-click", id);
+            break;
+        }
+        case "notification-action": {
+            const [id, action] = args;
+            events.emit("notification-action", id, action);
+            break;
+        }
+        case "notification-close": {
+            const [id] = args;
+            events.emit("notification-close", id);
+            break;
+        }
         default: {
             console.log(event, args);
         }
@@ -60,16 +167,44 @@ const listener = (event: string, ...args: any[]) => {
 
 export const _init = () => {
     _start = true;
-    return new Promise<void>((res) => {
-        lib.app_init(listener, (boxedIpc: any) => {
-            _boxedIpc = boxedIpc;
-            _ready = true;
-            res();
-            _waits.forEach((resolve) => resolve());
-        });
+    return new Promise<void>((res, rej) => {
+        const fail = (error: Error) => {
+            _startError = error;
+            rej(error);
+            _waits.forEach(({ reject }) => reject(error));
+        };
+        try {
+            lib.app_init(
+                listener,
+                (error: string | null, boxedIpc: any) => {
+                    if (error) {
+                        fail(new Error(error));
+                        return;
+                    }
+                    _boxedIpc = boxedIpc;
+                    _ready = true;
+                    res();
+                    _waits.forEach(({ resolve }) => resolve());
+                },
+                _options.appId || "",
+                _options.dataDir ?? null,
+                _options.devtools ?? true,
+                _options.headless ?? false,
+                _options.urlSchemes ?? []
+            );
+        } catch (error) {
+            fail(error as Error);
+        }
     });
 };
 
+export const configure = (options: AppOptions) => {
+    if (_start) {
+        throw new Error("app must be configured before the first window");
+    }
+    _options = { ..._options, ...options };
+};
+
 export const quit = async () => {
     const webviews = [...Webview.all];
     for (let browser of webviews) {
@@ -90,6 +225,192 @@ export const unsafe_quit = async () => {
     });
 };
 
+export const showMessageBox = async (
+    options: MessageBoxOptions,
+    parent?: Webview
+): Promise<number> => {
+    if (parent) {
+        await parent.waitUntilReady();
+    } else {
+        await _ensureStarted();
+    }
+    const buttons = options.buttons || ["OK"];
+    return new Promise((res) => {
+        lib.show_message_box(
+            getBoxedIpc(),
+            options.type || "info",
+            options.title || "",
+            options.message,
+            buttons,
+            options.defaultId || 0,
+            options.cancelId ?? buttons.length - 1,
+            parent ? parent.boxedWindowId : null,
+            res
+        );
+    });
+};
+
+export const showNotification = async (
+    options: NotificationOptions
+): Promise<number> => {
+    await _ensureStarted();
+    return new Promise((res, rej) => {
+        lib.show_notification(
+            getBoxedIpc(),
+            options.title,
+            options.body || "",
+            options.icon ?? null,
+            options.actions || [],
+            options.silent || false,
+            (error: string | null, id: number) => {
+                if (error) {
+                    rej(new Error(error));
+                } else {
+                    res(id);
+                }
+            }
+        );
+    });
+};
+
+// every open window in the order they were created
+export const listWindows = async (): Promise<WindowInfo[]> => {
+    await _ensureStarted();
+    return new Promise((res) => {
+        lib.list_windows(getBoxedIpc(), res);
+    });
+};
+
+export const getWindowByLabel = async (
+    label: string
+): Promise<Webview | undefined> => {
+    await _ensureStarted();
+    return new Promise((res) => {
+        lib.get_window_by_label(getBoxedIpc(), label, (id: number | null) => {
+            res(id === null ? undefined : Webview.fromId(id));
+        });
+    });
+};
+
+// Serves a subset of the WebDriver protocol on localhost. Sessions are
+// created with the token as a bearer token or `webarea:token` capability.
+export const startAutomation = async (
+    options: AutomationOptions = {}
+): Promise<AutomationServer> => {
+    await _ensureStarted();
+    const token = options.token ?? randomBytes(24).toString("hex");
+    return new Promise((res, rej) => {
+        lib.start_automation(
+            getBoxedIpc(),
+            options.port ?? 0,
+            token,
+            (error: string | null, port: number) => {
+                if (error) {
+                    rej(new Error(error));
+                } else {
+                    res({ port, token });
+                }
+            }
+        );
+    });
+};
+
+// Resolves with false when another instance of the app is already running,
+// it gets this process' argv and working directory as a "second-instance"
+// event. Defaults to the `appId` passed to `configure`.
+export const requestSingleInstanceLock = async (
+    appId: string = _options.appId || ""
+): Promise<boolean> => {
+    await _ensureStarted();
+    return new Promise((res, rej) => {
+        lib.request_single_instance_lock(
+            getBoxedIpc(),
+            appId,
+            (error: string | null, locked: boolean) => {
+                if (error) {
+                    rej(new Error(error));
+                } else {
+                    res(locked);
+                }
+            }
+        );
+    });
+};
+
+// Makes the desktop open `scheme:` urls with this app, they arrive as
+// "open-url" events when the scheme is in `urlSchemes`. Linux only.
+export const registerUrlScheme = async (
+    scheme: string,
+    options: UrlSchemeOptions = {}
+): Promise<void> => {
+    const command = options.command ?? [
+        process.execPath,
+        ...(process.argv[1] ? [resolve(process.argv[1])] : []),
+    ];
+    return new Promise((res, rej) => {
+        lib.register_url_scheme(
+            _options.appId || "",
+            scheme,
+            options.name ?? _options.appId ?? scheme,
+            command,
+            _settle(res, rej)
+        );
+    });
+};
+
+// resolves with false when the scheme wasn't registered
+export const unregisterUrlScheme = async (scheme: string): Promise<boolean> => {
+    return new Promise((res, rej) => {
+        lib.unregister_url_scheme(
+            _options.appId || "",
+            scheme,
+            (error: string | null, removed: boolean) => {
+                if (error) {
+                    rej(new Error(error));
+                } else {
+                    res(removed);
+                }
+            }
+        );
+    });
+};
+
+export const isUrlSchemeRegistered = async (
+    scheme: string
+): Promise<boolean> => {
+    return new Promise((res, rej) => {
+        lib.is_url_scheme_registered(
+            _options.appId || "",
+            scheme,
+            (error: string | null, registered: boolean) => {
+                if (error) {
+                    rej(new Error(error));
+                } else {
+                    res(registered);
+                }
+            }
+        );
+    });
+};
+
+// adapts the (error) => void callbacks used by fallible native calls
+export const _settle = (res: () => void, rej: (err: Error) => void) => {
+    return (error: string | null) => {
+        if (error) {
+            rej(new Error(error));
+        } else {
+            res();
+        }
+    };
+};
+
+export const _ensureStarted = () => {
+    if (_isStarted()) {
+        return _waitUntilReady();
+    }
+    return _init();
+};
+
 export const getBoxedIpc = () => {
     if (!_boxedIpc) {
         throw new Error("app must be initialized before use");
@@ -105,7 +426,10 @@ export const _waitUntilReady = () => {
     if (_ready) {
         return Promise.resolve();
     }
-    return new Promise<void>((resolve) => {
-        _waits.push(resolve);
+    if (_startError) {
+        return Promise.reject(_startError);
+    }
+    return new Promise<void>((resolve, reject) => {
+        _waits.push({ resolve, reject });
     });
 };
@@ -0,0 +1,72 @@
+import { EventEmitter } from "events";
+import * as app from "./app";
+import type { Rect } from "./types";
+import type { Webview } from "./webview";
+import lib from "./core";
+
+class ChildIpc extends EventEmitter {
+    constructor(private child: ChildWebview) {
+        super();
+    }
+
+    async send(channel: string, message: any) {
+        await this.child.evaluateScript(
+            `__NODE__.__emit('${channel}', '${JSON.stringify(message)}')`
+        );
+    }
+}
+
+// A webview laid over part of its window, with its own page and IPC.
+export class ChildWebview {
+    ipc = new ChildIpc(this);
+    removed = false;
+
+    constructor(readonly parent: Webview, readonly id: number) {}
+
+    async setBounds(bounds: Rect): Promise<void> {
+        if (this.removed) {
+            throw new Error("child webview is removed");
+        }
+        return new Promise((res, rej) => {
+            lib.set_child_webview_bounds(
+                app.getBoxedIpc(),
+                this.parent.boxedWindowId,
+                this.id,
+                bounds,
+                app._settle(res, rej)
+            );
+        });
+    }
+
+    async evaluateScript(script: string): Promise<void> {
+        if (this.removed) {
+            throw new Error("child webview is removed");
+        }
+        return new Promise((res, rej) => {
+            lib.evaluate_child_script(
+                app.getBoxedIpc(),
+                this.parent.boxedWindowId,
+                this.id,
+                script,
+                app._settle(res, rej)
+            );
+        });
+    }
+
+    async remove(): Promise<void> {
+        if (this.removed) {
+            throw new Error("child webview is removed");
+        }
+        this.removed = true;
+        this.ipc.removeAllListeners();
+        this.parent.children.delete(this.id);
+        return new Promise((res, rej) => {
+            lib.remove_child_webview(
+                app.getBoxedIpc(),
+                this.parent.boxedWindowId,
+                this.id,
+                app._settle(res, rej)
+            );
+        });
+    }
+}
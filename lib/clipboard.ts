@@ -0,0 +1,64 @@
+import * as app from "./app";
+import lib from "./core";
+import type { Bitmap, ClipboardFormat } from "./types";
+
+export const readText = async (): Promise<string | null> => {
+    await app._ensureStarted();
+    return new Promise((res) => {
+        lib.clipboard_read_text(app.getBoxedIpc(), res);
+    });
+};
+
+export const writeText = async (text: string): Promise<void> => {
+    await app._ensureStarted();
+    return new Promise((res, rej) => {
+        lib.clipboard_write_text(app.getBoxedIpc(), text, app._settle(res, rej));
+    });
+};
+
+export const writeHTML = async (
+    html: string,
+    altText?: string
+): Promise<void> => {
+    await app._ensureStarted();
+    return new Promise((res, rej) => {
+        lib.clipboard_write_html(
+            app.getBoxedIpc(),
+            html,
+            altText ?? null,
+            app._settle(res, rej)
+        );
+    });
+};
+
+export const readImage = async (): Promise<Bitmap | null> => {
+    await app._ensureStarted();
+    return new Promise((res) => {
+        lib.clipboard_read_image(
+            app.getBoxedIpc(),
+            (data: Buffer | null, width: number, height: number) => {
+                res(data ? { data, width, height } : null);
+            }
+        );
+    });
+};
+
+export const writeImage = async (bitmap: Bitmap): Promise<void> => {
+    await app._ensureStarted();
+    return new Promise((res, rej) => {
+        lib.clipboard_write_image(
+            app.getBoxedIpc(),
+            bitmap.data,
+            bitmap.width,
+            bitmap.height,
+            app._settle(res, rej)
+        );
+    });
+};
+
+export const formats = async (): Promise<ClipboardFormat[]> => {
+    await app._ensureStarted();
+    return new Promise((res) => {
+        lib.clipboard_formats(app.getBoxedIpc(), res);
+    });
+};
@@ -1,12 +1,42 @@
 import { Webview } from "./webview";
+import { ChildWebview } from "./child_webview";
 import * as _app from "./app";
+import * as _clipboard from "./clipboard";
+import * as _session from "./session";
 
-export { Webview };
+export { Webview, ChildWebview };
 
 export const app = {
     on: _app.events.on.bind(_app.events),
+    configure: _app.configure,
     quit: _app.quit,
     unsafe_quit: _app.unsafe_quit,
+    showMessageBox: _app.showMessageBox,
+    showNotification: _app.showNotification,
+    startAutomation: _app.startAutomation,
+    listWindows: _app.listWindows,
+    getWindowByLabel: _app.getWindowByLabel,
+    requestSingleInstanceLock: _app.requestSingleInstanceLock,
+    registerUrlScheme: _app.registerUrlScheme,
+    unregisterUrlScheme: _app.unregisterUrlScheme,
+    isUrlSchemeRegistered: _app.isUrlSchemeRegistered,
+};
+
+export const session = {
+    clearData: _session.clearData,
+    getDataUsage: _session.getDataUsage,
+    getCookies: _session.getCookies,
+    setCookie: _session.setCookie,
+    removeCookie: _session.removeCookie,
+};
+
+export const clipboard = {
+    readText: _clipboard.readText,
+    writeText: _clipboard.writeText,
+    writeHTML: _clipboard.writeHTML,
+    readImage: _clipboard.readImage,
+    writeImage: _clipboard.writeImage,
+    formats: _clipboard.formats,
 };
 
 async function test() {
@@ -0,0 +1,101 @@
+import * as app from "./app";
+import lib from "./core";
+import type { ClearDataOptions, Cookie, DataUsage } from "./types";
+
+// `partition` is null for windows created without one
+export const clearData = async (
+    partition: string | null,
+    options: ClearDataOptions = {}
+): Promise<void> => {
+    await app._ensureStarted();
+    return new Promise((res, rej) => {
+        lib.clear_data(
+            app.getBoxedIpc(),
+            partition,
+            options.cookies ?? true,
+            options.localStorage ?? true,
+            options.cache ?? true,
+            options.indexeddb ?? true,
+            app._settle(res, rej)
+        );
+    });
+};
+
+export const getDataUsage = async (
+    partition: string | null
+): Promise<DataUsage> => {
+    await app._ensureStarted();
+    return new Promise((res, rej) => {
+        lib.get_data_usage(
+            app.getBoxedIpc(),
+            partition,
+            (error: string | null, usage: DataUsage) => {
+                if (error) {
+                    rej(new Error(error));
+                } else {
+                    res(usage);
+                }
+            }
+        );
+    });
+};
+
+// Cookies are read through an open window of the partition, so these reject
+// when none is open.
+export const getCookies = async (
+    partition: string | null,
+    url: string
+): Promise<Cookie[]> => {
+    await app._ensureStarted();
+    return new Promise((res, rej) => {
+        lib.get_cookies(
+            app.getBoxedIpc(),
+            partition,
+            url,
+            (error: string | null, cookies: Cookie[]) => {
+                if (error) {
+                    rej(new Error(error));
+                } else {
+                    res(cookies);
+                }
+            }
+        );
+    });
+};
+
+export const setCookie = async (
+    partition: string | null,
+    cookie: Cookie
+): Promise<void> => {
+    await app._ensureStarted();
+    const { expires, sameSite, ...rest } = cookie;
+    // the native side treats missing fields as defaults, not nulls
+    const payload: Cookie = { ...rest };
+    if (expires != null) payload.expires = expires;
+    if (sameSite != null) payload.sameSite = sameSite;
+    return new Promise((res, rej) => {
+        lib.set_cookie(
+            app.getBoxedIpc(),
+            partition,
+            payload,
+            app._settle(res, rej)
+        );
+    });
+};
+
+export const removeCookie = async (
+    partition: string | null,
+    name: string,
+    url: string
+): Promise<void> => {
+    await app._ensureStarted();
+    return new Promise((res, rej) => {
+        lib.remove_cookie(
+            app.getBoxedIpc(),
+            partition,
+            name,
+            url,
+            app._settle(res, rej)
+        );
+    });
+};
@@ -1,8 +1,15 @@
+import type { Webview } from "./webview";
+
 export interface Size {
     width: number;
     height: number;
 }
 
+export interface Position {
+    x: number;
+    y: number;
+}
+
 export namespace Events {
     export type UserEvent = (userData: any) => void;
     export type GetInnerSize = (type: "getInnerSize", payload: Size) => {};
@@ -15,6 +22,60 @@ export interface Bitmap {
     data: Buffer;
 }
 
+export interface Rect {
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+}
+
+export type CaptureFormat = "png" | "jpeg";
+
+export interface CaptureOptions {
+    // part of the visible page in css pixels, the whole viewport by default
+    rect?: Rect;
+    format?: CaptureFormat;
+}
+
+export interface PrintOptions {
+    // print without showing the dialog
+    silent?: boolean;
+    printer?: string;
+}
+
+export type PageSize =
+    | "a3"
+    | "a4"
+    | "a5"
+    | "letter"
+    | "legal"
+    | "tabloid"
+    // in millimeters
+    | { width: number; height: number };
+
+// in millimeters
+export interface Margins {
+    top: number;
+    right: number;
+    bottom: number;
+    left: number;
+}
+
+export interface PdfOptions {
+    pageSize?: PageSize;
+    // the paper's default margins when not set
+    margins?: Margins;
+    landscape?: boolean;
+}
+
+export interface ChildWebviewOptions {
+    url: string;
+    // relative to the window's content area
+    bounds: Rect;
+    // grow and shrink with the window
+    autoResize?: { width?: boolean; height?: boolean };
+}
+
 export interface WebviewOptions {
     title?: string;
     devtools?: boolean;
@@ -24,5 +85,173 @@ export interface WebviewOptions {
     resizable?: boolean;
     width?: number;
     height?: number;
+    // runs after the built-in bridge and before `initializationScripts`
     preloadScript?: string;
+    // run in order at the start of every page
+    initializationScripts?: (string | InitializationScript)[];
+    fileDrop?: boolean;
+    fileDropPreventDefault?: boolean;
+    downloadHandler?: DownloadHandler | null;
+    // windows with the same partition keep their cookies and storage in the
+    // same directory, every window reads it when it opens
+    partition?: string | null;
+    // private window, nothing is persisted
+    ephemeral?: boolean;
+    // served as webarea://localhost/<path>
+    assetsDir?: string | null;
+    security?: SecurityOptions;
+    // page zoom factor, 1 is 100%
+    zoom?: number;
+    // zoom with Ctrl/Cmd and +, - or 0
+    zoomHotkeys?: boolean;
+    // stays above the parent and closes along with it, Linux and Windows only
+    parent?: Webview | null;
+    // blocks input to `parent` until closed
+    modal?: boolean;
+    // unique among open windows, see `app.getWindowByLabel`
+    label?: string | null;
+    // also sets what CSS `prefers-color-scheme` matches. On Linux GTK's dark
+    // preference is shared, the last window forcing a theme sets it for all
+    theme?: ThemeSetting;
+}
+
+export type Theme = "light" | "dark";
+
+// "system" follows the OS and its changes
+export type ThemeSetting = Theme | "system";
+
+export interface WindowInfo {
+    id: number;
+    label: string | null;
+    title: string;
+    visible: boolean;
+    focused: boolean;
+    // outer position and content size
+    bounds: Rect;
+}
+
+export interface SecurityOptions {
+    // origins allowed to use IPC, e.g. "https://example.com", null allows any
+    ipcOrigins?: string[] | null;
+    // set to false to reject IPC from every page loaded from the network
+    remoteIpc?: boolean;
+    // let pages loaded from file: urls use IPC like the app's own assets
+    fileIpc?: boolean;
+    // Content-Security-Policy for pages served from `assetsDir`
+    csp?: string | null;
+    // channels the page may use, "fs:*" matches every channel starting
+    // with "fs:", null allows any
+    allowedChannels?: string[] | null;
+    // checked before `allowedChannels`
+    deniedChannels?: string[];
+    // messages per second, further ones are dropped
+    ipcRateLimit?: number | null;
+}
+
+export type SecurityViolation = "ipc" | "drag-window" | "channel" | "rate-limit";
+
+export interface InitializationScript {
+    source: string;
+    // main frame only by default
+    allFrames?: boolean;
+}
+
+export interface AppOptions {
+    // names the default data directory, needed unless dataDir is given
+    appId?: string;
+    dataDir?: string;
+    // set to false to keep devtools closed in every window, builds without
+    // the "devtools" cargo feature never have them in release mode
+    devtools?: boolean;
+    // windows are never shown; on Linux an Xvfb display is started when
+    // there's no display to use
+    headless?: boolean;
+    // command line arguments with these schemes are emitted as "open-url",
+    // also when a later instance forwards them
+    urlSchemes?: string[];
+}
+
+export interface UrlSchemeOptions {
+    // shown when the desktop asks which app should open a link
+    name?: string;
+    // program and arguments the url is appended to, defaults to this
+    // node process and script
+    command?: string[];
+}
+
+export interface AutomationOptions {
+    // a free port is picked when not set
+    port?: number;
+    // a random one is generated when not set
+    token?: string;
+}
+
+export interface AutomationServer {
+    port: number;
+    token: string;
+}
+
+// resolves with the destination path, or null to deny the download
+export type DownloadHandler = (
+    url: string,
+    suggestedFilename: string
+) => string | null | Promise<string | null>;
+
+export type MessageBoxType = "info" | "warning" | "error" | "question";
+
+export interface MessageBoxOptions {
+    type?: MessageBoxType;
+    title?: string;
+    message: string;
+    buttons?: string[];
+    defaultId?: number;
+    cancelId?: number;
+}
+
+export type ClipboardFormat = "text" | "image";
+
+export interface NotificationAction {
+    // "default" is invoked by clicking the notification itself and is
+    // reported as notification-click, most desktops show it without a button
+    id: string;
+    title: string;
+}
+
+export interface NotificationOptions {
+    title: string;
+    body?: string;
+    icon?: string;
+    actions?: NotificationAction[];
+    silent?: boolean;
+}
+
+export interface ClearDataOptions {
+    cookies?: boolean;
+    localStorage?: boolean;
+    // on Linux the engine shares one cache between all partitions
+    cache?: boolean;
+    indexeddb?: boolean;
+}
+
+// sizes in bytes
+export interface DataUsage {
+    cookies: number;
+    localStorage: number;
+    cache: number;
+    indexeddb: number;
+    total: number;
+}
+
+export type CookieSameSite = "none" | "lax" | "strict";
+
+export interface Cookie {
+    name: string;
+    value: string;
+    domain: string;
+    path?: string;
+    // seconds since the unix epoch, null for session cookies
+    expires?: number | null;
+    secure?: boolean;
+    httpOnly?: boolean;
+    sameSite?: CookieSameSite | null;
 }
@@ -1,7 +1,18 @@
 import { EventEmitter } from "events";
 import * as app from "./app";
-import { preload } from "./preload";
-import type { Bitmap, WebviewOptions } from "./types";
+import { ChildWebview } from "./child_webview";
+import type {
+    Bitmap,
+    CaptureOptions,
+    ChildWebviewOptions,
+    InitializationScript,
+    PdfOptions,
+    Position,
+    PrintOptions,
+    SecurityViolation,
+    Theme,
+    WebviewOptions,
+} from "./types";
 import lib from "./core";
 
 class Ipc extends EventEmitter {
@@ -24,29 +35,69 @@ export declare interface Webview {
         listener: (width: number, height: number) => void
     ): this;
     on(event: "move", listener: (x: number, y: number) => void): this;
+    on(
+        event: "file-drop-hovered" | "file-drop-dropped",
+        listener: (paths: string[], position: Position | null) => void
+    ): this;
+    on(event: "file-drop-cancelled", listener: () => void): this;
+    on(event: "devtools-opened" | "devtools-closed", listener: () => void): this;
+    on(event: "zoom-changed", listener: (factor: number) => void): this;
+    on(event: "theme-changed", listener: (theme: Theme) => void): this;
+    on(
+        event: "security-violation",
+        listener: (
+            violation: SecurityViolation,
+            origin: string,
+            channel: string | null
+        ) => void
+    ): this;
+    on(
+        event: "download-started",
+        listener: (id: number, url: string, path: string) => void
+    ): this;
+    on(
+        event: "download-progress",
+        listener: (id: number, received: number, total: number) => void
+    ): this;
+    on(
+        event: "download-completed",
+        listener: (
+            id: number,
+            path: string | null,
+            received: number,
+            error: string | null
+        ) => void
+    ): this;
 }
 
 export class Webview extends EventEmitter {
     static all: Webview[] = [];
+    private static byId = new Map<number, Webview>();
     ready = false;
     private waits: (() => void)[] = [];
     private closed = false;
     ipc = new Ipc(this);
+    children = new Map<number, ChildWebview>();
     boxedWindowId: any;
+    // assigned by the native side once the window is created
+    id: number | null = null;
 
-    constructor(private options: WebviewOptions = {}) {
+    constructor(readonly options: WebviewOptions = {}) {
         super();
         Webview.all.push(this);
         const defaultPayload = this.defaultOptions();
         const payload = { ...defaultPayload, ...options };
+        const initializationScripts = [
+            payload.preloadScript,
+            ...payload.initializationScripts,
+        ]
+            .map(toInitializationScript)
+            .filter((script) => script.source !== "");
 
         // init app
         const init = async () => {
-            if (app._isStarted()) {
-                await app._waitUntilReady();
-            } else {
-                await app._init();
-            }
+            await app._ensureStarted();
+            await payload.parent?.waitUntilReady();
             lib.create_new_window(
                 app.getBoxedIpc(),
                 payload.title,
@@ -57,9 +108,33 @@ export class Webview extends EventEmitter {
                 payload.height,
                 payload.visible,
                 payload.resizable,
-                defaultPayload.preloadScript + (options.preloadScript || ""),
-                (boxedWindowId: any) => {
+                initializationScripts,
+                payload.fileDrop,
+                payload.fileDropPreventDefault,
+                !!payload.downloadHandler,
+                payload.partition,
+                payload.ephemeral,
+                payload.security.ipcOrigins ?? null,
+                payload.security.remoteIpc ?? true,
+                payload.security.fileIpc ?? false,
+                payload.security.csp ?? null,
+                payload.assetsDir,
+                payload.security.allowedChannels ?? null,
+                payload.security.deniedChannels ?? [],
+                payload.security.ipcRateLimit ?? null,
+                payload.zoom,
+                payload.zoomHotkeys,
+                payload.parent?.boxedWindowId ?? null,
+                payload.modal,
+                payload.label,
+                payload.theme,
+                (boxedWindowId: any, id: number) => {
                     this.boxedWindowId = boxedWindowId;
+                    this.id = id;
+                    // close() may already have been called while waiting
+                    if (!this.closed) {
+                        Webview.byId.set(id, this);
+                    }
                     this.ready = true;
                     this.waits.forEach((wait) => wait());
                 }
@@ -89,16 +164,34 @@ export class Webview extends EventEmitter {
         if (this.closed) {
             throw new Error("window is closed");
         }
-        this.closed = true;
-        this.ipc.removeAllListeners();
-        const index = Webview.all.indexOf(this);
-        Webview.all.splice(index, 1);
+        this._markClosed();
         await this.waitUntilReady();
         return new Promise((res) => {
             lib.close_window(app.getBoxedIpc(), this.boxedWindowId, res);
         });
     }
 
+    // also called for windows closed along with their parent
+    _markClosed() {
+        this.closed = true;
+        this.ipc.removeAllListeners();
+        // children go away along with the window
+        for (const child of this.children.values()) {
+            child.removed = true;
+            child.ipc.removeAllListeners();
+        }
+        this.children.clear();
+        const index = Webview.all.indexOf(this);
+        Webview.all.splice(index, 1);
+        if (this.id !== null) {
+            Webview.byId.delete(this.id);
+        }
+    }
+
+    static fromId(id: number): Webview | undefined {
+        return Webview.byId.get(id);
+    }
+
     async focus(): Promise<void> {
         if (this.closed) {
             throw new Error("window is closed");
@@ -224,6 +317,48 @@ export class Webview extends EventEmitter {
         });
     }
 
+    // takes effect on the next navigation, resolves with an id for
+    // `removeInitializationScript`
+    async addInitializationScript(
+        script: string | InitializationScript
+    ): Promise<number> {
+        if (this.closed) {
+            throw new Error("window is closed");
+        }
+        await this.waitUntilReady();
+        const { source, allFrames } = toInitializationScript(script);
+        return new Promise((res, rej) => {
+            lib.add_initialization_script(
+                app.getBoxedIpc(),
+                this.boxedWindowId,
+                source,
+                allFrames,
+                (error: string | null, id: number) => {
+                    if (error) {
+                        rej(new Error(error));
+                    } else {
+                        res(id);
+                    }
+                }
+            );
+        });
+    }
+
+    async removeInitializationScript(id: number): Promise<void> {
+        if (this.closed) {
+            throw new Error("window is closed");
+        }
+        await this.waitUntilReady();
+        return new Promise((res, rej) => {
+            lib.remove_initialization_script(
+                app.getBoxedIpc(),
+                this.boxedWindowId,
+                id,
+                app._settle(res, rej)
+            );
+        });
+    }
+
     async setSize(width: number, height: number): Promise<any> {
         if (this.closed) {
             throw new Error("window is closed");
@@ -310,6 +445,153 @@ export class Webview extends EventEmitter {
         });
     }
 
+    async setZoom(factor: number): Promise<void> {
+        if (this.closed) {
+            throw new Error("window is closed");
+        }
+        await this.waitUntilReady();
+        return new Promise((res) => {
+            lib.set_zoom(app.getBoxedIpc(), this.boxedWindowId, factor, res);
+        });
+    }
+
+    async getZoom(): Promise<number> {
+        if (this.closed) {
+            throw new Error("window is closed");
+        }
+        await this.waitUntilReady();
+        return new Promise((res) => {
+            lib.get_zoom(app.getBoxedIpc(), this.boxedWindowId, res);
+        });
+    }
+
+    // the theme the window is shown with, also when following the system
+    async getTheme(): Promise<Theme> {
+        if (this.closed) {
+            throw new Error("window is closed");
+        }
+        await this.waitUntilReady();
+        return new Promise((res) => {
+            lib.get_theme(app.getBoxedIpc(), this.boxedWindowId, res);
+        });
+    }
+
+    async addChildWebview(options: ChildWebviewOptions): Promise<ChildWebview> {
+        if (this.closed) {
+            throw new Error("window is closed");
+        }
+        await this.waitUntilReady();
+        return new Promise((res, rej) => {
+            lib.add_child_webview(
+                app.getBoxedIpc(),
+                this.boxedWindowId,
+                options.url,
+                options.bounds,
+                options.autoResize?.width ?? false,
+                options.autoResize?.height ?? false,
+                (error: string | null, id: number) => {
+                    if (error) {
+                        rej(new Error(error));
+                    } else {
+                        const child = new ChildWebview(this, id);
+                        this.children.set(id, child);
+                        res(child);
+                    }
+                }
+            );
+        });
+    }
+
+    // works for hidden windows as well
+    async capturePage(options: CaptureOptions = {}): Promise<Buffer> {
+        if (this.closed) {
+            throw new Error("window is closed");
+        }
+        await this.waitUntilReady();
+        return new Promise((res, rej) => {
+            lib.capture_page(
+                app.getBoxedIpc(),
+                this.boxedWindowId,
+                options.rect ?? null,
+                options.format ?? "png",
+                (error: string | null, image: Buffer) => {
+                    if (error) {
+                        rej(new Error(error));
+                    } else {
+                        res(image);
+                    }
+                }
+            );
+        });
+    }
+
+    // resolves with false when the print dialog was cancelled
+    async print(options: PrintOptions = {}): Promise<boolean> {
+        if (this.closed) {
+            throw new Error("window is closed");
+        }
+        await this.waitUntilReady();
+        return new Promise((res, rej) => {
+            lib.print(
+                app.getBoxedIpc(),
+                this.boxedWindowId,
+                options.silent ?? false,
+                options.printer ?? null,
+                (error: string | null, printed: boolean) => {
+                    if (error) {
+                        rej(new Error(error));
+                    } else {
+                        res(printed);
+                    }
+                }
+            );
+        });
+    }
+
+    async printToPDF(options: PdfOptions = {}): Promise<Buffer> {
+        if (this.closed) {
+            throw new Error("window is closed");
+        }
+        await this.waitUntilReady();
+        return new Promise((res, rej) => {
+            lib.print_to_pdf(
+                app.getBoxedIpc(),
+                this.boxedWindowId,
+                options.pageSize ?? "a4",
+                options.margins ?? null,
+                options.landscape ?? false,
+                (error: string | null, pdf: Buffer) => {
+                    if (error) {
+                        rej(new Error(error));
+                    } else {
+                        res(pdf);
+                    }
+                }
+            );
+        });
+    }
+
+    // resolves with whether the devtools are open afterwards
+    async toggleDevtools(): Promise<boolean> {
+        if (this.closed) {
+            throw new Error("window is closed");
+        }
+        await this.waitUntilReady();
+        return new Promise((res) => {
+            lib.toggle_devtools(app.getBoxedIpc(), this.boxedWindowId, res);
+        });
+    }
+
+    async isDevtoolsOpen(): Promise<boolean> {
+        if (this.closed) {
+            throw new Error("window is closed");
+        }
+        await this.waitUntilReady();
+        return new Promise((res) => {
+            lib.is_devtools_open(app.getBoxedIpc(), this.boxedWindowId, res);
+        });
+    }
+
     async setFrameless(frameless: boolean): Promise<void> {
         if (this.closed) {
             throw new Error("window is closed");
@@ -352,7 +634,21 @@ export class Webview extends EventEmitter {
             resizable: true,
             width: 800,
             height: 600,
-            preloadScript: preload,
+            preloadScript: "",
+            initializationScripts: [],
+            fileDrop: false,
+            fileDropPreventDefault: false,
+            downloadHandler: null,
+            partition: null,
+            ephemeral: false,
+            assetsDir: null,
+            security: {},
+            zoom: 1,
+            zoomHotkeys: false,
+            parent: null,
+            modal: false,
+            label: null,
+            theme: "system",
         };
         return defaultPayload;
     }
@@ -366,3 +662,10 @@ export class Webview extends EventEmitter {
         });
     }
 }
+
+const toInitializationScript = (
+    script: string | InitializationScript
+): Required<InitializationScript> =>
+    typeof script === "string"
+        ? { source: script, allFrames: false }
+        : { source: script.source, allFrames: !!script.allFrames };
@@ -0,0 +1,650 @@
+use crate::capture::CaptureCallback;
+use crate::window_ids::WindowIds;
+use crate::UserEvents;
+use serde_json::{json, Value};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use wry::{
+    application::{
+        dpi::{LogicalPosition, LogicalSize},
+        event_loop::EventLoopProxy,
+        window::{Window, WindowId},
+    },
+    webview::WebView,
+};
+
+const SCRIPT_TIMEOUT: Duration = Duration::from_secs(30);
+const PAGE_LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_TIMEOUT: Duration = Duration::from_secs(1);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const MAX_BODY: usize = 16 * 1024 * 1024;
+const REPLY_PREFIX: &str = "automation:";
+// set on the old page while navigating, the new page doesn't have it
+const NAVIGATING: &str = "__webareaNavigating";
+
+// What the server needs from the event loop, windows are addressed by handle.
+pub enum Command {
+    ListWindows,
+    // handle, function body and its arguments
+    Execute(String, String, Value),
+    Screenshot(String),
+    GetRect(String),
+    SetRect(String, WindowRect),
+    Close(String),
+}
+
+impl Command {
+    pub fn window(&self) -> Option<&str> {
+        match self {
+            Command::ListWindows => None,
+            Command::Execute(handle, _, _)
+            | Command::Screenshot(handle)
+            | Command::GetRect(handle)
+            | Command::SetRect(handle, _)
+            | Command::Close(handle) => Some(handle),
+        }
+    }
+}
+
+pub type Reply = Sender<Result<Value, WebDriverError>>;
+
+pub struct WebDriverError {
+    status: u16,
+    error: &'static str,
+    message: String,
+}
+
+impl WebDriverError {
+    fn new(status: u16, error: &'static str, message: impl Into<String>) -> Self {
+        WebDriverError {
+            status,
+            error,
+            message: message.into(),
+        }
+    }
+
+    pub fn no_such_window() -> Self {
+        Self::new(404, "no such window", "the window is closed")
+    }
+
+    pub fn javascript(message: impl Into<String>) -> Self {
+        Self::new(500, "javascript error", message)
+    }
+
+    pub fn unsupported(message: impl Into<String>) -> Self {
+        Self::new(500, "unsupported operation", message)
+    }
+
+    fn invalid_argument(message: impl Into<String>) -> Self {
+        Self::new(400, "invalid argument", message)
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "value": {
+                "error": self.error,
+                "message": self.message,
+                "stacktrace": "",
+            }
+        })
+    }
+}
+
+// in css pixels, missing fields are left as they are
+#[derive(Clone, Copy)]
+pub struct WindowRect {
+    x: Option<f64>,
+    y: Option<f64>,
+    width: Option<f64>,
+    height: Option<f64>,
+}
+
+impl WindowRect {
+    pub fn apply(&self, window: &Window) {
+        let current = window_rect(window);
+        let field = |value: Option<f64>, name: &str| {
+            value.unwrap_or_else(|| current[name].as_f64().unwrap_or_default())
+        };
+        if self.x.is_some() || self.y.is_some() {
+            let position = LogicalPosition::new(field(self.x, "x"), field(self.y, "y"));
+            window.set_outer_position(position);
+        }
+        if self.width.is_some() || self.height.is_some() {
+            let size = LogicalSize::new(field(self.width, "width"), field(self.height, "height"));
+            window.set_inner_size(size);
+        }
+    }
+}
+
+pub fn window_rect(window: &Window) -> Value {
+    let scale_factor = window.scale_factor();
+    let position = window
+        .outer_position()
+        .map(|position| position.to_logical::<f64>(scale_factor))
+        .unwrap_or_else(|_| LogicalPosition::new(0.0, 0.0));
+    let size = window.inner_size().to_logical::<f64>(scale_factor);
+    json!({
+        "x": position.x,
+        "y": position.y,
+        "width": size.width,
+        "height": size.height,
+    })
+}
+
+// Handles are the windows' ids in Node, in the order the windows were opened.
+pub fn window_handles<'a>(
+    window_ids: &WindowIds,
+    windows: impl Iterator<Item = &'a WindowId>,
+) -> Value {
+    let mut ids = windows
+        .filter_map(|window_id| window_ids.get(window_id))
+        .collect::<Vec<_>>();
+    ids.sort_unstable();
+    json!(ids.iter().map(u32::to_string).collect::<Vec<_>>())
+}
+
+pub fn find_window<'a>(
+    webviews: &'a HashMap<WindowId, WebView>,
+    window_ids: &WindowIds,
+    handle: &str,
+) -> Option<(WindowId, &'a WebView)> {
+    let window_id = window_ids.window_id(handle.parse().ok()?)?;
+    webviews.get(&window_id).map(|webview| (window_id, webview))
+}
+
+pub fn screenshot(reply: Reply) -> CaptureCallback {
+    Box::new(move |result| {
+        let result = result
+            .map(|png| Value::String(base64::encode(png)))
+            .map_err(WebDriverError::unsupported);
+        let _ = reply.send(result);
+    })
+}
+
+struct Pending {
+    window_id: WindowId,
+    // ids are easily guessed, only the script knows its nonce
+    nonce: String,
+    reply: Reply,
+    // the server has given up on the reply by then
+    deadline: Instant,
+}
+
+// Scripts run by the server. Their results come back as IPC messages, which
+// works on every engine and lets them await promises.
+pub struct Scripts {
+    next_id: u64,
+    pending: HashMap<u64, Pending>,
+}
+
+impl Scripts {
+    pub fn new() -> Self {
+        Scripts {
+            next_id: 1,
+            pending: HashMap::new(),
+        }
+    }
+
+    pub fn run(
+        &mut self,
+        window_id: WindowId,
+        webview: &WebView,
+        body: &str,
+        args: &Value,
+        reply: Reply,
+    ) {
+        // replies from pages that navigated away never come
+        let now = Instant::now();
+        self.pending.retain(|_, pending| pending.deadline > now);
+        let id = self.next_id;
+        self.next_id += 1;
+        let nonce = random_id();
+        let source = format!(
+            "(async () => {{
+                const reply = {{ id: {id}, nonce: {nonce} }};
+                try {{
+                    const value = await (function () {{\n{body}\n}}).apply(null, {args});
+                    reply.value = value === undefined ? null : value;
+                }} catch (err) {{
+                    reply.error = String(err && err.message ? err.message : err);
+                }}
+                let message;
+                try {{
+                    message = JSON.stringify(reply);
+                }} catch (err) {{
+                    delete reply.value;
+                    reply.error = \"result is not serializable\";
+                    message = JSON.stringify(reply);
+                }}
+                window.ipc.postMessage({prefix} + message);
+            }})();",
+            body = body,
+            args = args,
+            id = id,
+            nonce = json!(nonce),
+            prefix = json!(REPLY_PREFIX),
+        );
+        match webview.evaluate_script(&source) {
+            Ok(()) => {
+                let pending = Pending {
+                    window_id,
+                    nonce,
+                    reply,
+                    deadline: now + SCRIPT_TIMEOUT,
+                };
+                self.pending.insert(id, pending);
+            }
+            Err(err) => {
+                let _ = reply.send(Err(WebDriverError::javascript(err.to_string())));
+            }
+        }
+    }
+
+    // `message` is an IPC message from `window_id`, anything that isn't a
+    // reply to a script run there is ignored
+    pub fn resolve(&mut self, window_id: &WindowId, message: &str) {
+        let message: Value = match serde_json::from_str(message) {
+            Ok(message) => message,
+            Err(_) => return,
+        };
+        let id = match message.get("id").and_then(Value::as_u64) {
+            Some(id) => id,
+            None => return,
+        };
+        // pages can't settle scripts run in another window, or ones they
+        // didn't get to see
+        let nonce = message.get("nonce").and_then(Value::as_str).unwrap_or("");
+        match self.pending.get(&id) {
+            Some(pending)
+                if pending.window_id == *window_id && same_token(nonce, &pending.nonce) => {}
+            _ => return,
+        }
+        if let Some(pending) = self.pending.remove(&id) {
+            let result = match message.get("error").and_then(Value::as_str) {
+                Some(error) => Err(WebDriverError::javascript(error)),
+                None => Ok(message.get("value").cloned().unwrap_or(Value::Null)),
+            };
+            let _ = pending.reply.send(result);
+        }
+    }
+
+    // dropping the replies tells the server the window is gone
+    pub fn release(&mut self, window_id: &WindowId) {
+        self.pending
+            .retain(|_, pending| pending.window_id != *window_id);
+    }
+}
+
+pub fn parse_reply(message: &str) -> Option<&str> {
+    message.strip_prefix(REPLY_PREFIX)
+}
+
+// Starts a server for a subset of the WebDriver protocol on localhost and
+// returns its port. Creating a session needs the token, either as a bearer
+// token or as the `webarea:token` capability, the session id guards the rest.
+pub fn start(proxy: EventLoopProxy<UserEvents>, port: u16, token: String) -> Result<u16, String> {
+    let listener = TcpListener::bind(("127.0.0.1", port)).map_err(|err| err.to_string())?;
+    let port = listener.local_addr().map_err(|err| err.to_string())?.port();
+    let server = Arc::new(Server {
+        proxy: Mutex::new(proxy),
+        token,
+        sessions: Mutex::new(HashMap::new()),
+    });
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let server = server.clone();
+            thread::spawn(move || server.serve(stream));
+        }
+    });
+    Ok(port)
+}
+
+struct Request {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+    body: Value,
+}
+
+struct Server {
+    proxy: Mutex<EventLoopProxy<UserEvents>>,
+    token: String,
+    // session id to its current window handle
+    sessions: Mutex<HashMap<String, Option<String>>>,
+}
+
+impl Server {
+    fn serve(&self, stream: TcpStream) {
+        let _ = stream.set_read_timeout(Some(SCRIPT_TIMEOUT));
+        let request = match read_request(&stream) {
+            Some(request) => request,
+            None => return,
+        };
+        let (status, body) = match self.route(&request) {
+            Ok(value) => (200, json!({ "value": value })),
+            Err(err) => (err.status, err.to_json()),
+        };
+        respond(stream, status, &body);
+    }
+
+    fn route(&self, request: &Request) -> Result<Value, WebDriverError> {
+        let path = request.path.split('?').next().unwrap_or("");
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["status"]) => Ok(json!({ "ready": true, "message": "webarea automation" })),
+            ("POST", ["session"]) => self.new_session(request),
+            (method, ["session", session, command @ ..]) => {
+                if !self.sessions.lock().unwrap().contains_key(*session) {
+                    return Err(WebDriverError::new(
+                        404,
+                        "invalid session id",
+                        "no such session",
+                    ));
+                }
+                self.session_command(session, method, command, &request.body)
+            }
+            _ => Err(unknown_command()),
+        }
+    }
+
+    fn new_session(&self, request: &Request) -> Result<Value, WebDriverError> {
+        let header_token = request
+            .headers
+            .get("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "));
+        let capability_token = request
+            .body
+            .pointer("/capabilities/alwaysMatch/webarea:token")
+            .or_else(|| request.body.pointer("/desiredCapabilities/webarea:token"))
+            .and_then(Value::as_str);
+        let authorized = header_token
+            .or(capability_token)
+            .is_some_and(|token| same_token(token, &self.token));
+        if !authorized {
+            return Err(WebDriverError::new(
+                500,
+                "session not created",
+                "missing or wrong webarea:token",
+            ));
+        }
+        let session = random_id();
+        let handles = self.window_handles()?;
+        let current = handles.first().cloned();
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(session.clone(), current);
+        Ok(json!({
+            "sessionId": session,
+            "capabilities": {
+                "browserName": "webarea",
+                "platformName": std::env::consts::OS,
+            },
+        }))
+    }
+
+    fn session_command(
+        &self,
+        session: &str,
+        method: &str,
+        command: &[&str],
+        body: &Value,
+    ) -> Result<Value, WebDriverError> {
+        match (method, command) {
+            ("DELETE", []) => {
+                self.sessions.lock().unwrap().remove(session);
+                Ok(Value::Null)
+            }
+            ("GET", ["window"]) => self.current_window(session).map(Value::String),
+            ("POST", ["window"]) => {
+                let handle = string_argument(body, "handle")?;
+                if !self.window_handles()?.contains(&handle) {
+                    return Err(WebDriverError::no_such_window());
+                }
+                self.sessions
+                    .lock()
+                    .unwrap()
+                    .insert(session.to_string(), Some(handle));
+                Ok(Value::Null)
+            }
+            ("DELETE", ["window"]) => {
+                let window = self.current_window(session)?;
+                self.send(Command::Close(window), SCRIPT_TIMEOUT)
+            }
+            ("GET", ["window", "handles"]) => self.window_handles().map(|handles| json!(handles)),
+            ("GET", ["window", "rect"]) => {
+                let window = self.current_window(session)?;
+                self.send(Command::GetRect(window), SCRIPT_TIMEOUT)
+            }
+            ("POST", ["window", "rect"]) => {
+                let window = self.current_window(session)?;
+                let rect = WindowRect {
+                    x: number_argument(body, "x")?,
+                    y: number_argument(body, "y")?,
+                    width: number_argument(body, "width")?,
+                    height: number_argument(body, "height")?,
+                };
+                self.send(Command::SetRect(window, rect), SCRIPT_TIMEOUT)
+            }
+            ("POST", ["url"]) => {
+                let window = self.current_window(session)?;
+                let url = string_argument(body, "url")?;
+                self.navigate(window, &url)
+            }
+            ("GET", ["url"]) => {
+                let window = self.current_window(session)?;
+                self.execute(window, "return location.href", json!([]), SCRIPT_TIMEOUT)
+            }
+            ("GET", ["title"]) => {
+                let window = self.current_window(session)?;
+                self.execute(window, "return document.title", json!([]), SCRIPT_TIMEOUT)
+            }
+            ("POST", ["execute", "sync"]) => {
+                let window = self.current_window(session)?;
+                let script = string_argument(body, "script")?;
+                let args = body.get("args").cloned().unwrap_or_else(|| json!([]));
+                if !args.is_array() {
+                    return Err(WebDriverError::invalid_argument("args must be an array"));
+                }
+                self.execute(window, &script, args, SCRIPT_TIMEOUT)
+            }
+            ("GET", ["screenshot"]) => {
+                let window = self.current_window(session)?;
+                self.send(Command::Screenshot(window), SCRIPT_TIMEOUT)
+            }
+            _ => Err(unknown_command()),
+        }
+    }
+
+    fn current_window(&self, session: &str) -> Result<String, WebDriverError> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .get(session)
+            .cloned()
+            .flatten()
+            .ok_or_else(WebDriverError::no_such_window)
+    }
+
+    fn window_handles(&self) -> Result<Vec<String>, WebDriverError> {
+        let handles = self.send(Command::ListWindows, SCRIPT_TIMEOUT)?;
+        Ok(handles
+            .as_array()
+            .map(|handles| {
+                handles
+                    .iter()
+                    .filter_map(|handle| handle.as_str().map(|handle| handle.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    fn execute(
+        &self,
+        window: String,
+        script: &str,
+        args: Value,
+        timeout: Duration,
+    ) -> Result<Value, WebDriverError> {
+        self.send(Command::Execute(window, script.to_string(), args), timeout)
+    }
+
+    // resolves once the new page finished loading, like WebDriver does
+    fn navigate(&self, window: String, url: &str) -> Result<Value, WebDriverError> {
+        // moving to a fragment of the same page keeps the document
+        let script = format!(
+            "const target = new URL({url}, location.href);
+            const samePage = target.hash !== \"\"
+                && target.href.split(\"#\")[0] === location.href.split(\"#\")[0];
+            if (!samePage) window.{navigating} = true;
+            location.href = target.href;",
+            url = json!(url),
+            navigating = NAVIGATING,
+        );
+        // the page may be gone before it gets to reply
+        match self.execute(window.clone(), &script, json!([]), POLL_TIMEOUT) {
+            Err(err) if err.error != "script timeout" => return Err(err),
+            _ => {}
+        }
+        let loaded = format!(
+            "return document.readyState === \"complete\" && !window.{}",
+            NAVIGATING
+        );
+        let started = Instant::now();
+        while started.elapsed() < PAGE_LOAD_TIMEOUT {
+            match self.execute(window.clone(), &loaded, json!([]), POLL_TIMEOUT) {
+                Ok(Value::Bool(true)) => return Ok(Value::Null),
+                Err(err) if err.error == "no such window" => return Err(err),
+                _ => thread::sleep(POLL_INTERVAL),
+            }
+        }
+        Err(WebDriverError::new(
+            500,
+            "timeout",
+            "the page did not load in time",
+        ))
+    }
+
+    fn send(&self, command: Command, timeout: Duration) -> Result<Value, WebDriverError> {
+        let (reply, result) = mpsc::channel();
+        self.proxy
+            .lock()
+            .unwrap()
+            .send_event(UserEvents::Automation(command, reply))
+            .map_err(|_| WebDriverError::new(500, "unknown error", "the app is not running"))?;
+        match result.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => Err(WebDriverError::new(
+                500,
+                "script timeout",
+                "no reply in time",
+            )),
+            // replies are dropped along with their window
+            Err(RecvTimeoutError::Disconnected) => Err(WebDriverError::no_such_window()),
+        }
+    }
+}
+
+fn unknown_command() -> WebDriverError {
+    WebDriverError::new(404, "unknown command", "not supported by webarea")
+}
+
+fn string_argument(body: &Value, name: &str) -> Result<String, WebDriverError> {
+    body.get(name)
+        .and_then(Value::as_str)
+        .map(|value| value.to_string())
+        .ok_or_else(|| WebDriverError::invalid_argument(format!("{} must be a string", name)))
+}
+
+fn number_argument(body: &Value, name: &str) -> Result<Option<f64>, WebDriverError> {
+    match body.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_f64()
+            .map(Some)
+            .ok_or_else(|| WebDriverError::invalid_argument(format!("{} must be a number", name))),
+    }
+}
+
+// compares every byte so the time taken doesn't give the token away
+fn same_token(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+// RandomState is seeded by the OS, which is enough to make ids unguessable
+fn random_id() -> String {
+    let part = || RandomState::new().hash_one(SystemTime::now());
+    format!("{:016x}{:016x}", part(), part())
+}
+
+fn read_request(stream: &TcpStream) -> Option<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let path = parts.next()?.to_string();
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+    let length = headers
+        .get("content-length")
+        .and_then(|length| length.parse::<usize>().ok())
+        .unwrap_or(0);
+    if length > MAX_BODY {
+        return None;
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+    // a missing or broken body fails on the arguments later on
+    let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    Some(Request {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+fn respond(mut stream: TcpStream, status: u16, body: &Value) {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        _ => "Internal Server Error",
+    };
+    let body = body.to_string();
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {} {}\r\n\
+         Content-Type: application/json; charset=utf-8\r\n\
+         Content-Length: {}\r\n\
+         Cache-Control: no-cache\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+}
@@ -0,0 +1,182 @@
+// Renderer side of the IPC bridge, injected into the main frame of every
+// window before any page script runs.
+(function () {
+    "use strict";
+
+    // already installed, e.g. when a document is written into an existing one
+    if (Object.prototype.hasOwnProperty.call(window, "__NODE__")) {
+        return;
+    }
+
+    // Every message carries the window's key, Rust drops the ones posted by
+    // frames and scripts that go around the bridge.
+    const ipc = window.ipc;
+    const send = ipc.postMessage.bind(ipc);
+    const messageKey = "__MESSAGE_KEY__";
+    const postMessage = (message) => send(`${messageKey}:${message}`);
+    const listeners = [];
+
+    const define = (name, value) => {
+        Object.defineProperty(window, name, {
+            value: Object.freeze(value),
+            enumerable: false,
+            writable: false,
+            configurable: false,
+        });
+    };
+
+    // some engines already lock `window.ipc` down themselves
+    const descriptor = Object.getOwnPropertyDescriptor(window, "ipc");
+    if (!descriptor || descriptor.configurable) {
+        define("ipc", { postMessage });
+    }
+
+    const bridge = {
+        version: __BRIDGE_VERSION__,
+        dragWindow() {
+            postMessage("drag-window");
+        },
+        send(channel, payload) {
+            postMessage(`ipc:${JSON.stringify({ channel, payload })}`);
+        },
+        on(channel, listener) {
+            listeners.push({ channel, listener, once: false });
+        },
+        once(channel, listener) {
+            listeners.push({ channel, listener, once: true });
+        },
+        __emit(channel, payload) {
+            for (const item of [...listeners]) {
+                if (item.channel !== channel) {
+                    continue;
+                }
+                if (item.once) {
+                    listeners.splice(listeners.indexOf(item), 1);
+                }
+                item.listener(JSON.parse(payload));
+            }
+        },
+    };
+
+    // Drag regions: elements with `data-webarea-drag` (or a `--webarea-drag:
+    // drag` style) move the window, `data-webarea-drag="false"` and
+    // interactive elements inside them don't.
+    const INTERACTIVE =
+        'a[href], button, input, select, textarea, label, [contenteditable=""], [contenteditable="true"]';
+    const isDragRegion = (target) => {
+        for (let node = target; node instanceof Element; node = node.parentElement) {
+            if (node.matches(INTERACTIVE)) {
+                return false;
+            }
+            const attribute = node.getAttribute("data-webarea-drag");
+            if (attribute !== null) {
+                return attribute !== "false";
+            }
+            // kept for pages written against the old class based regions
+            if (node.classList.contains("drag-window")) {
+                return true;
+            }
+        }
+        if (!(target instanceof Element)) {
+            return false;
+        }
+        const style = getComputedStyle(target).getPropertyValue("--webarea-drag");
+        return style.trim() === "drag";
+    };
+
+    // Resize edges of frameless windows, only where the engine doesn't
+    // provide them itself.
+    const RESIZE_BORDER = 5;
+    const resizeEdges = __RESIZE_EDGES__;
+    let frameless = __FRAMELESS__;
+    let edgeCursor = false;
+    const edgeAt = (x, y) => {
+        if (!resizeEdges || !frameless) {
+            return "";
+        }
+        const vertical =
+            y < RESIZE_BORDER ? "n" : y >= window.innerHeight - RESIZE_BORDER ? "s" : "";
+        const horizontal =
+            x < RESIZE_BORDER ? "w" : x >= window.innerWidth - RESIZE_BORDER ? "e" : "";
+        return vertical + horizontal;
+    };
+
+    window.addEventListener(
+        "mousemove",
+        (e) => {
+            const edge = edgeAt(e.clientX, e.clientY);
+            if (edge) {
+                document.documentElement.style.cursor = `${edge}-resize`;
+                edgeCursor = true;
+            } else if (edgeCursor) {
+                document.documentElement.style.cursor = "";
+                edgeCursor = false;
+            }
+        },
+        true
+    );
+
+    window.addEventListener(
+        "mousedown",
+        (e) => {
+            if (e.button !== 0) {
+                return;
+            }
+            const edge = edgeAt(e.clientX, e.clientY);
+            if (edge) {
+                const scale = window.devicePixelRatio;
+                const x = Math.round(e.screenX * scale);
+                const y = Math.round(e.screenY * scale);
+                postMessage(`resize-window:${edge}:${x}:${y}`);
+                e.preventDefault();
+                return;
+            }
+            if (!isDragRegion(e.target)) {
+                return;
+            }
+            e.preventDefault();
+            postMessage(e.detail === 2 ? "maximize-window" : "drag-window");
+        },
+        true
+    );
+
+    // Ctrl/Cmd with +, - and 0, the zoom itself is handled in Rust
+    const zoomHotkeys = __ZOOM_HOTKEYS__;
+    const ZOOM_KEYS = { "+": "in", "=": "in", "-": "out", 0: "reset" };
+    window.addEventListener(
+        "keydown",
+        (e) => {
+            const step = ZOOM_KEYS[e.key];
+            if (!zoomHotkeys || !step || !(e.ctrlKey || e.metaKey) || e.altKey) {
+                return;
+            }
+            e.preventDefault();
+            postMessage(`zoom:${step}`);
+        },
+        true
+    );
+
+    // Window state is pushed from Rust on every page load and whenever it
+    // changes, `zoom` is only set where pages are scaled with CSS. Only Rust
+    // knows the window's key, pages can't push a state of their own.
+    const syncKey = "__SYNC_KEY__";
+    bridge.__sync = (key, state) => {
+        if (key !== syncKey) {
+            return;
+        }
+        frameless = state.frameless;
+        if (typeof state.zoom === "number") {
+            const apply = () => {
+                document.documentElement.style.zoom = String(state.zoom);
+            };
+            if (document.documentElement) {
+                apply();
+            } else {
+                document.addEventListener("DOMContentLoaded", apply, { once: true });
+            }
+        }
+    };
+
+    define("__NODE__", bridge);
+    postMessage("bridge-ready");
+})();
@@ -0,0 +1,64 @@
+use crate::init_script::InitScript;
+use crate::window_control::BRIDGE_RESIZE_EDGES;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash, Hasher};
+use wry::application::window::WindowId;
+
+// bumped whenever the shape of `window.__NODE__` changes
+pub const BRIDGE_VERSION: u32 = 1;
+
+const BRIDGE_SOURCE: &str = include_str!("bridge.js");
+
+thread_local! {
+    // seeded by the OS, windows are only created on the event loop thread
+    static KEYS: RandomState = RandomState::new();
+}
+
+pub fn bridge_script(window_id: WindowId, frameless: bool, zoom_hotkeys: bool) -> InitScript {
+    let source = BRIDGE_SOURCE
+        .replace("__BRIDGE_VERSION__", &BRIDGE_VERSION.to_string())
+        .replace("__SYNC_KEY__", &window_key(window_id, "sync"))
+        .replace("__MESSAGE_KEY__", &window_key(window_id, "message"))
+        .replace("__RESIZE_EDGES__", &BRIDGE_RESIZE_EDGES.to_string())
+        .replace("__FRAMELESS__", &frameless.to_string())
+        .replace("__ZOOM_HOTKEYS__", &zoom_hotkeys.to_string());
+    InitScript {
+        source,
+        all_frames: false,
+    }
+}
+
+// brings the page's copy of the window state up to date
+pub fn sync_script(window_id: WindowId, frameless: bool, css_zoom: Option<f64>) -> String {
+    let zoom = match css_zoom {
+        Some(zoom) => zoom.to_string(),
+        None => "null".to_string(),
+    };
+    format!(
+        "window.__NODE__ && window.__NODE__.__sync(\"{}\", {{ frameless: {}, zoom: {} }})",
+        window_key(window_id, "sync"),
+        frameless,
+        zoom
+    )
+}
+
+// Messages sent through the bridge start with the window's key. Frames and
+// scripts that go around it reach the same handler, but can't know the key.
+pub fn strip_message_key(window_id: WindowId, message: &str) -> Option<&str> {
+    let (key, message) = message.split_once(':')?;
+    if key == window_key(window_id, "message") {
+        Some(message)
+    } else {
+        None
+    }
+}
+
+// the same for every page of a window, nothing a page can work out
+fn window_key(window_id: WindowId, purpose: &str) -> String {
+    KEYS.with(|keys| {
+        let mut hasher = keys.build_hasher();
+        window_id.hash(&mut hasher);
+        purpose.hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    })
+}
@@ -0,0 +1,122 @@
+pub use platform::capture_page;
+
+#[derive(Clone, Copy)]
+pub enum CaptureFormat {
+    Png,
+    Jpeg,
+}
+
+impl CaptureFormat {
+    pub fn parse(format: &str) -> Option<Self> {
+        match format {
+            "png" => Some(CaptureFormat::Png),
+            "jpeg" | "jpg" => Some(CaptureFormat::Jpeg),
+            _ => None,
+        }
+    }
+}
+
+// part of the visible page, in css pixels
+#[derive(Clone, Copy)]
+pub struct CaptureRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+pub type CaptureCallback = Box<dyn FnOnce(Result<Vec<u8>, String>) + Send>;
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{CaptureCallback, CaptureFormat, CaptureRect};
+    use gtk::{cairo, gdk, prelude::*};
+    use std::convert::TryFrom;
+    use webkit2gtk::{gio, SnapshotOptions, SnapshotRegion, WebViewExt};
+    use wry::webview::{WebView, WebviewExtUnix};
+
+    const JPEG_QUALITY: &str = "90";
+
+    pub fn capture_page(
+        webview: &WebView,
+        rect: Option<CaptureRect>,
+        format: CaptureFormat,
+        callback: CaptureCallback,
+    ) {
+        let webkit_webview = webview.webview();
+        // A window that was never shown has no size yet, the page is laid out
+        // at the window's size so it can still be rendered.
+        if webkit_webview.allocated_width() <= 1 {
+            let size = webview.window().inner_size();
+            let scale = webview.window().scale_factor();
+            let size = size.to_logical::<i32>(scale);
+            webkit_webview.size_allocate(&gdk::Rectangle::new(0, 0, size.width, size.height));
+        }
+        webkit_webview.snapshot(
+            SnapshotRegion::Visible,
+            SnapshotOptions::NONE,
+            None::<&gio::Cancellable>,
+            move |result| {
+                let result = result
+                    .map_err(|err| err.to_string())
+                    .and_then(|surface| encode(surface, rect, format));
+                callback(result);
+            },
+        );
+    }
+
+    fn encode(
+        surface: cairo::Surface,
+        rect: Option<CaptureRect>,
+        format: CaptureFormat,
+    ) -> Result<Vec<u8>, String> {
+        let (scale_x, scale_y) = surface.device_scale();
+        let image = cairo::ImageSurface::try_from(surface)
+            .map_err(|_| "snapshot is not an image".to_string())?;
+        let page_width = (image.width() as f64 / scale_x) as i32;
+        let page_height = (image.height() as f64 / scale_y) as i32;
+        let rect = rect.unwrap_or(CaptureRect {
+            x: 0,
+            y: 0,
+            width: page_width,
+            height: page_height,
+        });
+        // only the part of the rect that's on the page
+        let x = rect.x.max(0);
+        let y = rect.y.max(0);
+        let width = (rect.x + rect.width).min(page_width) - x;
+        let height = (rect.y + rect.height).min(page_height) - y;
+        if width <= 0 || height <= 0 {
+            return Err("capture rect is outside of the page".to_string());
+        }
+        // the snapshot has device pixels, the image keeps their resolution
+        let left = (x as f64 * scale_x).round() as i32;
+        let top = (y as f64 * scale_y).round() as i32;
+        let right = (((x + width) as f64 * scale_x).round() as i32).min(image.width());
+        let bottom = (((y + height) as f64 * scale_y).round() as i32).min(image.height());
+        let pixbuf = gdk::pixbuf_get_from_surface(&image, left, top, right - left, bottom - top)
+            .ok_or_else(|| "could not read the snapshot".to_string())?;
+        let saved = match format {
+            CaptureFormat::Png => pixbuf.save_to_bufferv("png", &[]),
+            CaptureFormat::Jpeg => pixbuf.save_to_bufferv("jpeg", &[("quality", JPEG_QUALITY)]),
+        };
+        saved.map_err(|err| err.to_string())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::{CaptureCallback, CaptureFormat, CaptureRect};
+    use wry::webview::WebView;
+
+    pub fn capture_page(
+        _webview: &WebView,
+        _rect: Option<CaptureRect>,
+        _format: CaptureFormat,
+        callback: CaptureCallback,
+    ) {
+        callback(Err(
+            "capturing pages is not supported on this platform".to_string()
+        ));
+    }
+}
@@ -0,0 +1,369 @@
+use crate::bridge;
+use crate::security::{self, PageUrl, RateLimited, RateLimiter, SecurityPolicy, Violation};
+use crate::UserEvents;
+use std::collections::{hash_map::Entry, HashMap};
+use wry::{
+    application::{
+        event_loop::EventLoopProxy,
+        window::{Window, WindowId},
+    },
+    webview::WebView,
+};
+
+// in css pixels, relative to the window's content area
+#[derive(Clone, Copy)]
+pub struct Bounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+pub struct ChildOptions {
+    pub url: String,
+    pub bounds: Bounds,
+    // grow and shrink along with the window
+    pub auto_resize_width: bool,
+    pub auto_resize_height: bool,
+}
+
+pub enum ChildIpc {
+    Forward(String),
+    // bridge housekeeping, window controls don't apply to children, and
+    // messages over the rate limit
+    Ignore,
+    Reject(Violation, String, Option<String>),
+}
+
+struct Child {
+    bounds: Bounds,
+    auto_resize_width: bool,
+    auto_resize_height: bool,
+    // every child has a limit of its own, like a window
+    rate_limiter: RateLimiter,
+    page_url: PageUrl,
+    view: platform::View,
+}
+
+struct Host {
+    // window size the children's bounds were last adjusted to
+    size: (i32, i32),
+    children: HashMap<u64, Child>,
+    platform: platform::Host,
+}
+
+// Webviews placed over a window's own webview. The host is only set up with
+// the first child, windows without children stay as they are.
+pub struct ChildWebviews {
+    next_id: u64,
+    policies: HashMap<WindowId, SecurityPolicy>,
+    hosts: HashMap<WindowId, Host>,
+}
+
+impl ChildWebviews {
+    pub fn new() -> Self {
+        ChildWebviews {
+            next_id: 1,
+            policies: HashMap::new(),
+            hosts: HashMap::new(),
+        }
+    }
+
+    // children share the IPC policy of their window
+    pub fn register(&mut self, window_id: WindowId, security: SecurityPolicy) {
+        self.policies.insert(window_id, security);
+    }
+
+    pub(crate) fn add(
+        &mut self,
+        window_id: WindowId,
+        webview: &WebView,
+        options: ChildOptions,
+        proxy: EventLoopProxy<UserEvents>,
+    ) -> Result<u64, String> {
+        let id = self.next_id;
+        let rate_limit = self
+            .policies
+            .get(&window_id)
+            .and_then(|security| security.rate_limit);
+        let host = match self.hosts.entry(window_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(Host {
+                size: logical_size(webview.window()),
+                children: HashMap::new(),
+                platform: platform::host(webview)?,
+            }),
+        };
+        let bridge = bridge::bridge_script(window_id, false, false);
+        let page_url = PageUrl::default();
+        let on_message = move |message| {
+            let _ = proxy.send_event(UserEvents::ChildIpc(window_id, id, message));
+        };
+        let view = platform::create(
+            &host.platform,
+            webview,
+            &options,
+            &bridge,
+            &page_url,
+            on_message,
+        )?;
+        self.next_id += 1;
+        host.children.insert(
+            id,
+            Child {
+                bounds: options.bounds,
+                auto_resize_width: options.auto_resize_width,
+                auto_resize_height: options.auto_resize_height,
+                rate_limiter: RateLimiter::new(rate_limit),
+                page_url,
+                view,
+            },
+        );
+        Ok(id)
+    }
+
+    pub fn set_bounds(
+        &mut self,
+        window_id: WindowId,
+        id: u64,
+        bounds: Bounds,
+    ) -> Result<(), String> {
+        let child = self.child_mut(window_id, id)?;
+        child.bounds = bounds;
+        platform::set_bounds(&child.view, bounds);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, window_id: WindowId, id: u64) -> Result<(), String> {
+        let host = self.hosts.get_mut(&window_id).ok_or_else(|| no_child(id))?;
+        let child = host.children.remove(&id).ok_or_else(|| no_child(id))?;
+        platform::remove(&host.platform, &child.view);
+        Ok(())
+    }
+
+    pub fn evaluate_script(
+        &mut self,
+        window_id: WindowId,
+        id: u64,
+        script: &str,
+    ) -> Result<(), String> {
+        let child = self.child_mut(window_id, id)?;
+        platform::evaluate_script(&child.view, script);
+        Ok(())
+    }
+
+    // keeps auto resizing children in step with the window
+    pub fn resized(&mut self, window_id: &WindowId, window: &Window) {
+        let host = match self.hosts.get_mut(window_id) {
+            Some(host) => host,
+            None => return,
+        };
+        let size = logical_size(window);
+        let (delta_width, delta_height) = (size.0 - host.size.0, size.1 - host.size.1);
+        host.size = size;
+        for child in host.children.values_mut() {
+            if child.auto_resize_width {
+                child.bounds.width = (child.bounds.width + delta_width).max(0);
+            }
+            if child.auto_resize_height {
+                child.bounds.height = (child.bounds.height + delta_height).max(0);
+            }
+            if child.auto_resize_width || child.auto_resize_height {
+                platform::set_bounds(&child.view, child.bounds);
+            }
+        }
+    }
+
+    // same checks as for the window itself
+    pub fn ipc(&self, window_id: &WindowId, id: u64, message: &str) -> ChildIpc {
+        let (security, child) = match (self.policies.get(window_id), self.child(window_id, id)) {
+            (Some(security), Some(child)) => (security, child),
+            // removed while the message was queued
+            _ => return ChildIpc::Ignore,
+        };
+        // the sender went around the bridge, e.g. from a frame
+        let message = match bridge::strip_message_key(*window_id, message) {
+            Some(message) => message,
+            None => return ChildIpc::Reject(Violation::Ipc, String::new(), None),
+        };
+        let message = match message.strip_prefix("ipc:") {
+            Some(message) => message,
+            None => return ChildIpc::Ignore,
+        };
+        let origin = security::origin(&child.page_url.get());
+        if !security.allows_ipc(&origin) {
+            return ChildIpc::Reject(Violation::Ipc, origin, None);
+        }
+        match child.rate_limiter.check() {
+            RateLimited::No => {}
+            RateLimited::Started => return ChildIpc::Reject(Violation::RateLimit, origin, None),
+            RateLimited::Ongoing => return ChildIpc::Ignore,
+        }
+        match security::message_channel(message) {
+            Some(channel) if security.allows_channel(&channel) => {
+                ChildIpc::Forward(message.to_string())
+            }
+            channel => ChildIpc::Reject(Violation::Channel, origin, channel),
+        }
+    }
+
+    pub fn release(&mut self, window_id: &WindowId) {
+        self.policies.remove(window_id);
+        self.hosts.remove(window_id);
+    }
+
+    fn child(&self, window_id: &WindowId, id: u64) -> Option<&Child> {
+        self.hosts.get(window_id)?.children.get(&id)
+    }
+
+    fn child_mut(&mut self, window_id: WindowId, id: u64) -> Result<&mut Child, String> {
+        self.hosts
+            .get_mut(&window_id)
+            .and_then(|host| host.children.get_mut(&id))
+            .ok_or_else(|| no_child(id))
+    }
+}
+
+fn no_child(id: u64) -> String {
+    format!("no child webview with id {}", id)
+}
+
+fn logical_size(window: &Window) -> (i32, i32) {
+    let size = window.inner_size().to_logical::<i32>(window.scale_factor());
+    (size.width, size.height)
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{Bounds, ChildOptions};
+    use crate::init_script::InitScript;
+    use crate::security::PageUrl;
+    use gtk::prelude::*;
+    use webkit2gtk::{
+        gio, UserContentInjectedFrames, UserContentManager, UserContentManagerExt, UserScript,
+        UserScriptInjectionTime, WebViewExt,
+    };
+    use wry::webview::{WebView, WebviewExtUnix};
+
+    // what wry sets up for its own webviews
+    const IPC_SCRIPT: &str = r#"Object.defineProperty(window, "ipc", {
+        value: Object.freeze({
+            postMessage: (message) => window.webkit.messageHandlers.ipc.postMessage(message),
+        }),
+    });"#;
+
+    // The window's webview is moved into an overlay, children are laid on
+    // top of it and only take input within their bounds.
+    pub struct Host {
+        overlay: gtk::Overlay,
+    }
+
+    pub type View = webkit2gtk::WebView;
+
+    pub fn host(webview: &WebView) -> Result<Host, String> {
+        let main: gtk::Widget = webview.webview().upcast_ref::<gtk::Widget>().clone();
+        let parent = main
+            .parent()
+            .and_then(|parent| parent.downcast::<gtk::Box>().ok())
+            .ok_or_else(|| "the window's webview can't be moved".to_string())?;
+        let overlay = gtk::Overlay::new();
+        parent.remove(&main);
+        overlay.add(&main);
+        parent.pack_start(&overlay, true, true, 0);
+        overlay.show();
+        Ok(Host { overlay })
+    }
+
+    pub fn create<F: Fn(String) + 'static>(
+        host: &Host,
+        webview: &WebView,
+        options: &ChildOptions,
+        bridge: &InitScript,
+        page_url: &PageUrl,
+        on_message: F,
+    ) -> Result<View, String> {
+        let main = webview.webview();
+        let context = main
+            .context()
+            .ok_or_else(|| "webview has no context".to_string())?;
+        // a content manager of its own, the window's scripts stay out
+        let manager = UserContentManager::new();
+        manager.register_script_message_handler("ipc");
+        manager.connect_script_message_received(Some("ipc"), move |_, result| {
+            if let Some(value) = result.js_value() {
+                on_message(value.to_string());
+            }
+        });
+        for source in &[IPC_SCRIPT, bridge.source.as_str()] {
+            manager.add_script(&UserScript::new(
+                source,
+                UserContentInjectedFrames::TopFrame,
+                UserScriptInjectionTime::Start,
+                &[],
+                &[],
+            ));
+        }
+        let mut builder = webkit2gtk::WebView::builder()
+            .web_context(&context)
+            .user_content_manager(&manager);
+        if let Some(settings) = WebViewExt::settings(&*main) {
+            builder = builder.settings(&settings);
+        }
+        let view = builder.build();
+        page_url.attach_view(&view);
+        view.set_halign(gtk::Align::Start);
+        view.set_valign(gtk::Align::Start);
+        set_bounds(&view, options.bounds);
+        host.overlay.add_overlay(&view);
+        view.show();
+        view.load_uri(&options.url);
+        Ok(view)
+    }
+
+    pub fn set_bounds(view: &View, bounds: Bounds) {
+        view.set_margin_start(bounds.x.max(0));
+        view.set_margin_top(bounds.y.max(0));
+        view.set_size_request(bounds.width.max(0), bounds.height.max(0));
+    }
+
+    pub fn remove(host: &Host, view: &View) {
+        host.overlay.remove(view);
+    }
+
+    pub fn evaluate_script(view: &View, script: &str) {
+        view.run_javascript(script, None::<&gio::Cancellable>, |_| {});
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::{Bounds, ChildOptions};
+    use crate::init_script::InitScript;
+    use crate::security::PageUrl;
+    use wry::webview::WebView;
+
+    // wry puts exactly one webview into a window here
+    pub type Host = ();
+    pub type View = ();
+
+    pub fn host(_webview: &WebView) -> Result<Host, String> {
+        Err("child webviews are not supported on this platform".to_string())
+    }
+
+    pub fn create<F: Fn(String) + 'static>(
+        _host: &Host,
+        _webview: &WebView,
+        _options: &ChildOptions,
+        _bridge: &InitScript,
+        _page_url: &PageUrl,
+        _on_message: F,
+    ) -> Result<View, String> {
+        Err("child webviews are not supported on this platform".to_string())
+    }
+
+    pub fn set_bounds(_view: &View, _bounds: Bounds) {}
+
+    pub fn remove(_host: &Host, _view: &View) {}
+
+    pub fn evaluate_script(_view: &View, _script: &str) {}
+}
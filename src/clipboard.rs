@@ -0,0 +1,85 @@
+use arboard::{Clipboard, ImageData};
+use std::borrow::Cow;
+
+pub struct ClipboardImage {
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+// The clipboard is opened lazily and kept for the lifetime of the event loop,
+// on X11 the owner has to stay alive to keep serving what it wrote.
+pub struct SystemClipboard {
+    clipboard: Option<Clipboard>,
+}
+
+impl SystemClipboard {
+    pub fn new() -> Self {
+        SystemClipboard { clipboard: None }
+    }
+
+    fn get(&mut self) -> Result<&mut Clipboard, String> {
+        if self.clipboard.is_none() {
+            let clipboard = Clipboard::new().map_err(|err| err.to_string())?;
+            self.clipboard = Some(clipboard);
+        }
+        Ok(self.clipboard.as_mut().unwrap())
+    }
+
+    pub fn read_text(&mut self) -> Option<String> {
+        self.get().ok()?.get_text().ok()
+    }
+
+    pub fn write_text(&mut self, text: String) -> Result<(), String> {
+        self.get()?.set_text(text).map_err(|err| err.to_string())
+    }
+
+    pub fn write_html(&mut self, html: String, alt_text: Option<String>) -> Result<(), String> {
+        self.get()?
+            .set_html(html, alt_text)
+            .map_err(|err| err.to_string())
+    }
+
+    pub fn read_image(&mut self) -> Option<ClipboardImage> {
+        let image = self.get().ok()?.get_image().ok()?;
+        Some(ClipboardImage {
+            width: image.width as u32,
+            height: image.height as u32,
+            rgba: image.bytes.into_owned(),
+        })
+    }
+
+    pub fn write_image(&mut self, image: ClipboardImage) -> Result<(), String> {
+        let expected_len = image.width as usize * image.height as usize * 4;
+        if image.rgba.len() != expected_len {
+            return Err(format!(
+                "expected {} bytes of rgba data for a {}x{} image, got {}",
+                expected_len,
+                image.width,
+                image.height,
+                image.rgba.len()
+            ));
+        }
+        let image = ImageData {
+            width: image.width as usize,
+            height: image.height as usize,
+            bytes: Cow::Owned(image.rgba),
+        };
+        self.get()?.set_image(image).map_err(|err| err.to_string())
+    }
+
+    pub fn formats(&mut self) -> Vec<&'static str> {
+        let mut formats = Vec::new();
+        let clipboard = match self.get() {
+            Ok(clipboard) => clipboard,
+            Err(_) => return formats,
+        };
+        if clipboard.get_text().is_ok() {
+            formats.push("text");
+        }
+        if clipboard.get_image().is_ok() {
+            formats.push("image");
+        }
+        formats
+    }
+}
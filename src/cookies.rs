@@ -0,0 +1,230 @@
+pub use platform::{get_cookies, remove_cookie, set_cookie};
+
+#[derive(Clone)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+    // seconds since the unix epoch, `None` for session cookies
+    pub expires: Option<f64>,
+    pub secure: bool,
+    pub http_only: bool,
+    pub same_site: Option<String>,
+}
+
+pub type CookiesCallback = Box<dyn FnOnce(Result<Vec<Cookie>, String>) + Send>;
+pub type CookieResultCallback = Box<dyn FnOnce(Result<(), String>) + Send>;
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{Cookie, CookieResultCallback, CookiesCallback};
+    use soup::SameSitePolicy;
+    use std::sync::{Arc, Mutex};
+    use webkit2gtk::{
+        gio,
+        glib::{ObjectExt, SendWeakRef},
+        CookieManager, CookieManagerExt, WebContextExt, WebViewExt,
+    };
+    use wry::webview::{WebView, WebviewExtUnix};
+
+    fn cookie_manager(webview: &WebView) -> Result<CookieManager, String> {
+        webview
+            .webview()
+            .context()
+            .and_then(|context| context.cookie_manager())
+            .ok_or_else(|| "cookie store is not available".to_string())
+    }
+
+    fn cookie_managers(webviews: &[&WebView]) -> Result<Vec<CookieManager>, String> {
+        webviews
+            .iter()
+            .map(|webview| cookie_manager(webview))
+            .collect()
+    }
+
+    // Calls `callback` once `count` operations are done, with the first error
+    // if any.
+    fn join(
+        count: usize,
+        callback: CookieResultCallback,
+    ) -> impl Fn(Result<(), String>) + Clone + Send + 'static {
+        let pending = Arc::new(Mutex::new((count, Some(callback), None)));
+        move |result| {
+            let mut pending = pending.lock().unwrap();
+            pending.0 -= 1;
+            if let Err(err) = result {
+                pending.2.get_or_insert(err);
+            }
+            if pending.0 == 0 {
+                if let Some(callback) = pending.1.take() {
+                    callback(pending.2.take().map_or(Ok(()), Err));
+                }
+            }
+        }
+    }
+
+    pub fn get_cookies(webview: &WebView, url: &str, callback: CookiesCallback) {
+        let manager = match cookie_manager(webview) {
+            Ok(manager) => manager,
+            Err(err) => return callback(Err(err)),
+        };
+        manager.cookies(url, None::<&gio::Cancellable>, move |result| {
+            let result = result
+                .map(|cookies| cookies.into_iter().map(from_soup).collect())
+                .map_err(|err| err.to_string());
+            callback(result);
+        });
+    }
+
+    // Every window of a partition has a cookie store of its own, changes go
+    // to all of them.
+    pub fn set_cookie(webviews: &[&WebView], cookie: Cookie, callback: CookieResultCallback) {
+        let managers = match cookie_managers(webviews) {
+            Ok(managers) => managers,
+            Err(err) => return callback(Err(err)),
+        };
+        let done = join(managers.len(), callback);
+        for manager in managers {
+            let done = done.clone();
+            let mut cookie = to_soup(cookie.clone());
+            manager.add_cookie(&mut cookie, None::<&gio::Cancellable>, move |result| {
+                done(result.map_err(|err| err.to_string()));
+            });
+        }
+    }
+
+    pub fn remove_cookie(
+        webviews: &[&WebView],
+        name: String,
+        url: &str,
+        callback: CookieResultCallback,
+    ) {
+        let managers = match cookie_managers(webviews) {
+            Ok(managers) => managers,
+            Err(err) => return callback(Err(err)),
+        };
+        let done = join(managers.len(), callback);
+        for manager in managers {
+            remove_from(&manager, name.clone(), url, done.clone());
+        }
+    }
+
+    fn remove_from<F>(manager: &CookieManager, name: String, url: &str, done: F)
+    where
+        F: Fn(Result<(), String>) + Send + 'static,
+    {
+        // the reply comes back on this thread, but the callback has to be `Send`
+        let delete_manager: SendWeakRef<CookieManager> = manager.downgrade().into();
+        manager.cookies(url, None::<&gio::Cancellable>, move |result| {
+            let delete_manager = match delete_manager.upgrade() {
+                Some(manager) => manager,
+                None => return done(Err("cookie store is gone".to_string())),
+            };
+            let cookies = match result {
+                Ok(cookies) => cookies,
+                Err(err) => return done(Err(err.to_string())),
+            };
+            let mut matching: Vec<soup::Cookie> = cookies
+                .into_iter()
+                .filter_map(|mut cookie| match cookie.name() {
+                    Some(cookie_name) if cookie_name.as_str() == name => Some(cookie),
+                    _ => None,
+                })
+                .collect();
+            if matching.is_empty() {
+                return done(Ok(()));
+            }
+            // done once every matching cookie is gone
+            let deleted = join(matching.len(), Box::new(done));
+            for cookie in matching.iter_mut() {
+                let deleted = deleted.clone();
+                delete_manager.delete_cookie(cookie, None::<&gio::Cancellable>, move |result| {
+                    deleted(result.map_err(|err| err.to_string()));
+                });
+            }
+        });
+    }
+
+    fn from_soup(mut cookie: soup::Cookie) -> Cookie {
+        let same_site = match cookie.same_site_policy() {
+            SameSitePolicy::None => Some("none".to_string()),
+            SameSitePolicy::Lax => Some("lax".to_string()),
+            SameSitePolicy::Strict => Some("strict".to_string()),
+            _ => None,
+        };
+        Cookie {
+            name: cookie
+                .name()
+                .map(|name| name.to_string())
+                .unwrap_or_default(),
+            value: cookie
+                .value()
+                .map(|value| value.to_string())
+                .unwrap_or_default(),
+            domain: cookie
+                .domain()
+                .map(|domain| domain.to_string())
+                .unwrap_or_default(),
+            path: cookie
+                .path()
+                .map(|path| path.to_string())
+                .unwrap_or_default(),
+            expires: cookie.expires().map(|mut date| date.to_time_t() as f64),
+            secure: cookie.is_secure(),
+            http_only: cookie.is_http_only(),
+            same_site,
+        }
+    }
+
+    fn to_soup(cookie: Cookie) -> soup::Cookie {
+        // -1 makes it a session cookie, `expires` below overrides it
+        let mut soup_cookie = soup::Cookie::new(
+            &cookie.name,
+            &cookie.value,
+            &cookie.domain,
+            &cookie.path,
+            -1,
+        );
+        if let Some(expires) = cookie.expires {
+            soup_cookie.set_expires(&mut soup::Date::from_time_t(expires as _));
+        }
+        soup_cookie.set_secure(cookie.secure);
+        soup_cookie.set_http_only(cookie.http_only);
+        let same_site = match cookie.same_site.as_deref() {
+            Some("strict") => Some(SameSitePolicy::Strict),
+            Some("lax") => Some(SameSitePolicy::Lax),
+            Some("none") => Some(SameSitePolicy::None),
+            _ => None,
+        };
+        if let Some(same_site) = same_site {
+            soup_cookie.set_same_site_policy(same_site);
+        }
+        soup_cookie
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::{Cookie, CookieResultCallback, CookiesCallback};
+    use wry::webview::WebView;
+
+    const UNSUPPORTED: &str = "cookies are not supported on this platform";
+
+    pub fn get_cookies(_webview: &WebView, _url: &str, callback: CookiesCallback) {
+        callback(Err(UNSUPPORTED.to_string()));
+    }
+
+    pub fn set_cookie(_webviews: &[&WebView], _cookie: Cookie, callback: CookieResultCallback) {
+        callback(Err(UNSUPPORTED.to_string()));
+    }
+
+    pub fn remove_cookie(
+        _webviews: &[&WebView],
+        _name: String,
+        _url: &str,
+        callback: CookieResultCallback,
+    ) {
+        callback(Err(UNSUPPORTED.to_string()));
+    }
+}
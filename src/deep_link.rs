@@ -0,0 +1,395 @@
+pub struct Registration {
+    pub app_id: String,
+    pub scheme: String,
+    // shown by the desktop when asking which app should open a link
+    pub name: String,
+    // program and arguments, the url is passed after them
+    pub command: Vec<String>,
+}
+
+// arguments that are urls with one of `schemes`, in order
+pub fn urls<I: IntoIterator<Item = String>>(args: I, schemes: &[String]) -> Vec<String> {
+    args.into_iter()
+        .filter(|arg| {
+            let scheme = match arg.split_once(':') {
+                Some((scheme, _)) => scheme,
+                None => return false,
+            };
+            schemes
+                .iter()
+                .any(|expected| expected.eq_ignore_ascii_case(scheme))
+        })
+        .collect()
+}
+
+pub fn register(registration: &Registration) -> Result<(), String> {
+    check_scheme(&registration.scheme)?;
+    if registration.command.is_empty() {
+        return Err("a command is needed to open urls with".to_string());
+    }
+    platform::register(registration)
+}
+
+// false when there was nothing to remove
+pub fn unregister(app_id: &str, scheme: &str) -> Result<bool, String> {
+    check_scheme(scheme)?;
+    platform::unregister(app_id, scheme)
+}
+
+pub fn is_registered(app_id: &str, scheme: &str) -> Result<bool, String> {
+    check_scheme(scheme)?;
+    platform::is_registered(app_id, scheme)
+}
+
+// RFC 3986: a letter followed by letters, digits, "+", "-" or "."
+fn check_scheme(scheme: &str) -> Result<(), String> {
+    let mut chars = scheme.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("\"{}\" is not a valid url scheme", scheme))
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::Registration;
+    use std::io::ErrorKind;
+    use std::path::{Path, PathBuf};
+    use std::process::{Command, Stdio};
+
+    const DEFAULTS_SECTION: &str = "[Default Applications]";
+
+    // Writes a desktop entry handling the scheme into the user's applications
+    // dir under $XDG_DATA_HOME and makes it the scheme's default in
+    // $XDG_CONFIG_HOME/mimeapps.list. Desktops don't pick a default for new
+    // entries themselves, links would keep going to an older handler.
+    pub fn register(registration: &Registration) -> Result<(), String> {
+        let path = entry_path(&registration.app_id, &registration.scheme)?;
+        let exec: Vec<String> = registration
+            .command
+            .iter()
+            .map(|arg| exec_arg(arg))
+            .collect();
+        let name: String = registration
+            .name
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect();
+        let entry = format!(
+            "[Desktop Entry]\n\
+             Type=Application\n\
+             Name={}\n\
+             Exec={} %u\n\
+             Terminal=false\n\
+             NoDisplay=true\n\
+             MimeType={};\n",
+            name,
+            exec.join(" "),
+            mime_type(&registration.scheme)
+        );
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+        std::fs::write(&path, entry).map_err(|err| err.to_string())?;
+        update_database(&path);
+        update_default(&registration.scheme, &path, true)
+    }
+
+    pub fn unregister(app_id: &str, scheme: &str) -> Result<bool, String> {
+        let path = entry_path(app_id, scheme)?;
+        if !path.exists() {
+            return Ok(false);
+        }
+        std::fs::remove_file(&path).map_err(|err| err.to_string())?;
+        update_database(&path);
+        update_default(scheme, &path, false)?;
+        Ok(true)
+    }
+
+    pub fn is_registered(app_id: &str, scheme: &str) -> Result<bool, String> {
+        let entry = match std::fs::read_to_string(entry_path(app_id, scheme)?) {
+            Ok(entry) => entry,
+            Err(_) => return Ok(false),
+        };
+        let mime_type = mime_type(scheme);
+        Ok(entry
+            .lines()
+            .filter_map(|line| line.strip_prefix("MimeType="))
+            .flat_map(|types| types.split(';'))
+            .any(|registered| registered.eq_ignore_ascii_case(&mime_type)))
+    }
+
+    fn mime_type(scheme: &str) -> String {
+        format!("x-scheme-handler/{}", scheme.to_ascii_lowercase())
+    }
+
+    fn entry_path(app_id: &str, scheme: &str) -> Result<PathBuf, String> {
+        let app_id: String = app_id
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => c,
+                _ => '_',
+            })
+            .collect();
+        if app_id.is_empty() {
+            return Err("an app id is needed to register url schemes".to_string());
+        }
+        let data_dir = dirs::data_dir().ok_or_else(|| "no data directory".to_string())?;
+        let file_name = format!("{}-{}.desktop", app_id, scheme.to_ascii_lowercase());
+        Ok(data_dir.join("applications").join(file_name))
+    }
+
+    // Quoting as the desktop entry spec wants it for Exec, the key file format
+    // then unescapes backslashes once more.
+    fn exec_arg(arg: &str) -> String {
+        let arg = arg.replace('%', "%%");
+        let reserved = |c: char| " \t\n\"'\\><~|&;$*?#()`".contains(c);
+        if !arg.is_empty() && !arg.chars().any(reserved) {
+            return arg;
+        }
+        let mut quoted = String::from("\"");
+        for c in arg.chars() {
+            if matches!(c, '"' | '`' | '$' | '\\') {
+                quoted.push('\\');
+            }
+            quoted.push(c);
+        }
+        quoted.push('"');
+        quoted.replace('\\', "\\\\")
+    }
+
+    // Sets the scheme's default to the entry at `path`, or takes it away again
+    // while it's still the entry. Other lines are kept as they are.
+    fn update_default(scheme: &str, path: &Path, set: bool) -> Result<(), String> {
+        let entry = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let dir = dirs::config_dir().ok_or_else(|| "no config directory".to_string())?;
+        let list_path = dir.join("mimeapps.list");
+        let list = match std::fs::read_to_string(&list_path) {
+            Ok(list) => list,
+            Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err.to_string()),
+        };
+        let edited = edit_defaults(&list, &mime_type(scheme), &entry, set);
+        if edited == list {
+            return Ok(());
+        }
+        std::fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+        std::fs::write(&list_path, edited).map_err(|err| err.to_string())
+    }
+
+    fn edit_defaults(list: &str, mime_type: &str, entry: &str, set: bool) -> String {
+        let default = format!("{}={}", mime_type, entry);
+        let mut lines = Vec::new();
+        let mut in_defaults = false;
+        // nothing to add when taking the default away
+        let mut done = !set;
+        for line in list.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') {
+                // the section ends without the type, it goes last
+                if in_defaults && !done {
+                    lines.push(default.clone());
+                    done = true;
+                }
+                in_defaults = trimmed == DEFAULTS_SECTION;
+            } else if in_defaults {
+                if let Some((key, value)) = trimmed.split_once('=') {
+                    if key.trim() == mime_type {
+                        if set && !done {
+                            lines.push(default.clone());
+                            done = true;
+                        }
+                        // another app's default stays when removing ours
+                        if set || value.trim().trim_end_matches(';') == entry {
+                            continue;
+                        }
+                    }
+                }
+            }
+            lines.push(line.to_string());
+        }
+        if !done {
+            if !in_defaults {
+                lines.push(DEFAULTS_SECTION.to_string());
+            }
+            lines.push(default);
+        }
+        let mut list = lines.join("\n");
+        if !list.is_empty() {
+            list.push('\n');
+        }
+        list
+    }
+
+    // refreshes the dir's mime cache, desktops without the tool read the
+    // entries directly
+    fn update_database(path: &Path) {
+        if let Some(dir) = path.parent() {
+            let _ = Command::new("update-desktop-database")
+                .arg(dir)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::Registration;
+
+    pub fn register(_registration: &Registration) -> Result<(), String> {
+        Err("registering url schemes is not supported on this platform".to_string())
+    }
+
+    pub fn unregister(_app_id: &str, _scheme: &str) -> Result<bool, String> {
+        Err("registering url schemes is not supported on this platform".to_string())
+    }
+
+    pub fn is_registered(_app_id: &str, _scheme: &str) -> Result<bool, String> {
+        Err("registering url schemes is not supported on this platform".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn only_arguments_with_a_registered_scheme_are_urls() {
+        let args = strings(&[
+            "/usr/bin/app",
+            "--flag",
+            "myapp://open?id=1",
+            "other://open",
+            "MyApp:compose",
+            "myapp",
+            "notmyapp://open",
+        ]);
+        let schemes = strings(&["myapp", "web+app"]);
+        assert_eq!(urls(args, &schemes), ["myapp://open?id=1", "MyApp:compose"]);
+        assert!(urls(strings(&["myapp://open"]), &[]).is_empty());
+    }
+
+    #[test]
+    fn invalid_schemes_are_refused() {
+        for scheme in &["", "1app", "my app", "my_app", "app:"] {
+            assert!(check_scheme(scheme).is_err(), "{:?} was accepted", scheme);
+            assert!(unregister("app", scheme).is_err());
+        }
+        for scheme in &["a", "myapp", "web+app", "x-my.app-2"] {
+            assert!(check_scheme(scheme).is_ok(), "{:?} was refused", scheme);
+        }
+    }
+
+    #[test]
+    fn a_command_is_needed() {
+        let registration = Registration {
+            app_id: "app".to_string(),
+            scheme: "myapp".to_string(),
+            name: "App".to_string(),
+            command: Vec::new(),
+        };
+        assert!(register(&registration).is_err());
+    }
+
+    // the only test touching XDG_DATA_HOME and XDG_CONFIG_HOME, the
+    // environment is shared
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn registrations_are_desktop_entries_in_the_data_dir() {
+        let home = std::env::temp_dir().join(format!("webarea-deep-link-{}", std::process::id()));
+        let data_home = home.join("data");
+        let config_home = home.join("config");
+        std::env::set_var("XDG_DATA_HOME", &data_home);
+        std::env::set_var("XDG_CONFIG_HOME", &config_home);
+        let registration = Registration {
+            app_id: "com.example/app".to_string(),
+            scheme: "MyApp".to_string(),
+            name: "Example\nApp".to_string(),
+            command: strings(&["/opt/Example App/app", "--open", "100%"]),
+        };
+
+        assert!(!is_registered("com.example/app", "myapp").unwrap());
+        register(&registration).unwrap();
+        let path = data_home
+            .join("applications")
+            .join("com.example_app-myapp.desktop");
+        let entry = std::fs::read_to_string(&path).unwrap();
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+        assert!(entry.contains("\nName=Example App\n"));
+        assert!(entry.contains("\nExec=\"/opt/Example App/app\" --open 100%% %u\n"));
+        assert!(entry.contains("\nMimeType=x-scheme-handler/myapp;\n"));
+        assert!(is_registered("com.example/app", "MYAPP").unwrap());
+        assert!(!is_registered("com.example/app", "other").unwrap());
+        let mimeapps = config_home.join("mimeapps.list");
+        assert_eq!(
+            std::fs::read_to_string(&mimeapps).unwrap(),
+            "[Default Applications]\nx-scheme-handler/myapp=com.example_app-myapp.desktop\n"
+        );
+
+        assert!(unregister("com.example/app", "myapp").unwrap());
+        assert!(!path.exists());
+        assert!(!unregister("com.example/app", "myapp").unwrap());
+        assert!(!is_registered("com.example/app", "myapp").unwrap());
+        assert_eq!(
+            std::fs::read_to_string(&mimeapps).unwrap(),
+            "[Default Applications]\n"
+        );
+
+        // an older default is replaced, everything else stays
+        std::fs::write(
+            &mimeapps,
+            "[Default Applications]\n\
+             x-scheme-handler/myapp=other.desktop;\n\
+             text/html=browser.desktop\n\
+             [Added Associations]\n\
+             x-scheme-handler/myapp=other.desktop;\n",
+        )
+        .unwrap();
+        register(&registration).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&mimeapps).unwrap(),
+            "[Default Applications]\n\
+             x-scheme-handler/myapp=com.example_app-myapp.desktop\n\
+             text/html=browser.desktop\n\
+             [Added Associations]\n\
+             x-scheme-handler/myapp=other.desktop;\n"
+        );
+        assert!(unregister("com.example/app", "myapp").unwrap());
+        assert_eq!(
+            std::fs::read_to_string(&mimeapps).unwrap(),
+            "[Default Applications]\n\
+             text/html=browser.desktop\n\
+             [Added Associations]\n\
+             x-scheme-handler/myapp=other.desktop;\n"
+        );
+
+        // the section is added after others when it's missing
+        std::fs::write(
+            &mimeapps,
+            "[Added Associations]\ntext/plain=editor.desktop;\n",
+        )
+        .unwrap();
+        register(&registration).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&mimeapps).unwrap(),
+            "[Added Associations]\n\
+             text/plain=editor.desktop;\n\
+             [Default Applications]\n\
+             x-scheme-handler/myapp=com.example_app-myapp.desktop\n"
+        );
+        unregister("com.example/app", "myapp").unwrap();
+        let _ = std::fs::remove_dir_all(&home);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::UserEvents;
+use std::collections::HashSet;
+use wry::{
+    application::{event_loop::EventLoopProxy, window::WindowId},
+    webview::WebView,
+};
+
+pub(crate) use platform::connect;
+
+// wry always keeps devtools in debug builds, release builds need the feature
+pub const COMPILED: bool = cfg!(any(debug_assertions, feature = "devtools"));
+
+// Which windows may open devtools, after the build and the app wide policy.
+pub struct Devtools {
+    allowed: bool,
+    windows: HashSet<WindowId>,
+}
+
+impl Devtools {
+    pub fn new(allowed: bool) -> Self {
+        Devtools {
+            allowed: allowed && COMPILED,
+            windows: HashSet::new(),
+        }
+    }
+
+    // whether a window asking for devtools gets them
+    pub fn enabled(&self, requested: bool) -> bool {
+        self.allowed && requested
+    }
+
+    pub fn register(&mut self, window_id: WindowId, enabled: bool) {
+        if enabled {
+            self.windows.insert(window_id);
+        }
+    }
+
+    pub fn release(&mut self, window_id: &WindowId) {
+        self.windows.remove(window_id);
+    }
+
+    pub fn is_open(&self, webview: &WebView, window_id: &WindowId) -> bool {
+        self.windows.contains(window_id) && engine_is_open(webview)
+    }
+
+    // Opens or closes the devtools and returns whether they're open afterwards.
+    pub(crate) fn set_open(
+        &self,
+        webview: &WebView,
+        window_id: WindowId,
+        open: bool,
+        proxy: &EventLoopProxy<UserEvents>,
+    ) -> bool {
+        if !self.windows.contains(&window_id) {
+            return false;
+        }
+        let was_open = engine_is_open(webview);
+        engine_set_open(webview, open);
+        // WebKitGTK reports this on its own, even for devtools opened from
+        // the context menu. WebView2 can't tell whether they're open, so only
+        // "opened" is ever reported there.
+        if !cfg!(target_os = "linux") && was_open != open {
+            let _ = proxy.send_event(UserEvents::Devtools(window_id, open));
+        }
+        open
+    }
+}
+
+#[cfg(any(debug_assertions, feature = "devtools"))]
+fn engine_is_open(webview: &WebView) -> bool {
+    webview.is_devtools_open()
+}
+
+#[cfg(any(debug_assertions, feature = "devtools"))]
+fn engine_set_open(webview: &WebView, open: bool) {
+    if open {
+        webview.open_devtools();
+    } else {
+        webview.close_devtools();
+    }
+}
+
+#[cfg(not(any(debug_assertions, feature = "devtools")))]
+fn engine_is_open(_webview: &WebView) -> bool {
+    false
+}
+
+#[cfg(not(any(debug_assertions, feature = "devtools")))]
+fn engine_set_open(_webview: &WebView, _open: bool) {}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use crate::UserEvents;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use webkit2gtk::{WebInspectorExt, WebViewExt};
+    use wry::{
+        application::{event_loop::EventLoopProxy, window::WindowId},
+        webview::{WebView, WebviewExtUnix},
+    };
+
+    pub(crate) fn connect(
+        webview: &WebView,
+        window_id: WindowId,
+        proxy: EventLoopProxy<UserEvents>,
+    ) {
+        let inspector = match webview.webview().inspector() {
+            Some(inspector) => inspector,
+            None => return,
+        };
+        // "bring-to-front" fires every time the inspector is shown
+        let open = Rc::new(Cell::new(false));
+        let opened = open.clone();
+        let opened_proxy = proxy.clone();
+        inspector.connect_bring_to_front(move |_| {
+            if !opened.replace(true) {
+                let _ = opened_proxy.send_event(UserEvents::Devtools(window_id, true));
+            }
+            false
+        });
+        inspector.connect_closed(move |_| {
+            if open.replace(false) {
+                let _ = proxy.send_event(UserEvents::Devtools(window_id, false));
+            }
+        });
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use crate::UserEvents;
+    use wry::{
+        application::{event_loop::EventLoopProxy, window::WindowId},
+        webview::WebView,
+    };
+
+    pub(crate) fn connect(
+        _webview: &WebView,
+        _window_id: WindowId,
+        _proxy: EventLoopProxy<UserEvents>,
+    ) {
+    }
+}
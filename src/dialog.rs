@@ -0,0 +1,156 @@
+use wry::application::window::Window;
+
+#[cfg(target_os = "linux")]
+use gtk::prelude::*;
+#[cfg(target_os = "linux")]
+use std::cell::RefCell;
+#[cfg(target_os = "linux")]
+use wry::application::platform::unix::WindowExtUnix;
+
+pub enum MessageBoxType {
+    Info,
+    Warning,
+    Error,
+    Question,
+}
+
+impl MessageBoxType {
+    pub fn from_str(value: &str) -> Self {
+        match value {
+            "warning" => MessageBoxType::Warning,
+            "error" => MessageBoxType::Error,
+            "question" => MessageBoxType::Question,
+            _ => MessageBoxType::Info,
+        }
+    }
+}
+
+pub struct MessageBoxOptions {
+    pub kind: MessageBoxType,
+    pub title: String,
+    pub message: String,
+    pub buttons: Vec<String>,
+    pub default_id: usize,
+    pub cancel_id: usize,
+}
+
+impl MessageBoxOptions {
+    pub fn fatal_error(message: String) -> Self {
+        MessageBoxOptions {
+            kind: MessageBoxType::Error,
+            title: "Fatal error".to_string(),
+            message,
+            buttons: vec!["OK".to_string()],
+            default_id: 0,
+            cancel_id: 0,
+        }
+    }
+
+    fn buttons(&self) -> Vec<String> {
+        if self.buttons.is_empty() {
+            vec!["OK".to_string()]
+        } else {
+            self.buttons.clone()
+        }
+    }
+}
+
+/// Shows a modal message box and calls `on_response` with the index of the
+/// clicked button once it's dismissed. The event loop keeps running meanwhile.
+#[cfg(target_os = "linux")]
+pub fn show_message_box<F>(options: MessageBoxOptions, parent: Option<&Window>, on_response: F)
+where
+    F: FnOnce(usize) + 'static,
+{
+    let dialog = build_gtk_dialog(&options, parent);
+    let cancel_id = options.cancel_id;
+    let on_response = RefCell::new(Some(on_response));
+    dialog.connect_response(move |dialog, response| {
+        let on_response = on_response.borrow_mut().take();
+        if let Some(on_response) = on_response {
+            on_response(response_to_index(response, cancel_id));
+        }
+        unsafe { dialog.destroy() };
+    });
+    dialog.show_all();
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn show_message_box<F>(options: MessageBoxOptions, parent: Option<&Window>, on_response: F)
+where
+    F: FnOnce(usize) + 'static,
+{
+    on_response(show_message_box_blocking(options, parent));
+}
+
+/// Same as `show_message_box` but blocks until the dialog is dismissed, for
+/// places where the event loop can't be relied on anymore (e.g. panic hook).
+#[cfg(target_os = "linux")]
+pub fn show_message_box_blocking(options: MessageBoxOptions, parent: Option<&Window>) -> usize {
+    let dialog = build_gtk_dialog(&options, parent);
+    let response = dialog.run();
+    unsafe { dialog.destroy() };
+    response_to_index(response, options.cancel_id)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn show_message_box_blocking(options: MessageBoxOptions, parent: Option<&Window>) -> usize {
+    use rfd::{MessageButtons, MessageDialog, MessageLevel};
+
+    let level = match options.kind {
+        MessageBoxType::Warning => MessageLevel::Warning,
+        MessageBoxType::Error => MessageLevel::Error,
+        MessageBoxType::Info | MessageBoxType::Question => MessageLevel::Info,
+    };
+    // native dialogs here only know "ok" and "ok/cancel", so the first button
+    // is mapped to "ok" and everything else to the cancel button
+    let buttons = if options.buttons().len() > 1 {
+        MessageButtons::OkCancel
+    } else {
+        MessageButtons::Ok
+    };
+    let mut dialog = MessageDialog::new()
+        .set_level(level)
+        .set_title(&options.title)
+        .set_description(&options.message)
+        .set_buttons(buttons);
+    if let Some(parent) = parent {
+        dialog = dialog.set_parent(parent);
+    }
+    if dialog.show() {
+        0
+    } else {
+        options.cancel_id
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn build_gtk_dialog(options: &MessageBoxOptions, parent: Option<&Window>) -> gtk::MessageDialog {
+    let message_type = match options.kind {
+        MessageBoxType::Info => gtk::MessageType::Info,
+        MessageBoxType::Warning => gtk::MessageType::Warning,
+        MessageBoxType::Error => gtk::MessageType::Error,
+        MessageBoxType::Question => gtk::MessageType::Question,
+    };
+    let dialog = gtk::MessageDialog::new(
+        parent.map(|window| window.gtk_window()),
+        gtk::DialogFlags::MODAL | gtk::DialogFlags::DESTROY_WITH_PARENT,
+        message_type,
+        gtk::ButtonsType::None,
+        &options.message,
+    );
+    dialog.set_title(&options.title);
+    for (index, label) in options.buttons().iter().enumerate() {
+        dialog.add_button(label, gtk::ResponseType::Other(index as u16));
+    }
+    dialog.set_default_response(gtk::ResponseType::Other(options.default_id as u16));
+    dialog
+}
+
+#[cfg(target_os = "linux")]
+fn response_to_index(response: gtk::ResponseType, cancel_id: usize) -> usize {
+    match response {
+        gtk::ResponseType::Other(index) => index as usize,
+        _ => cancel_id,
+    }
+}
@@ -0,0 +1,478 @@
+pub(crate) use platform::{connect, resolve};
+
+pub enum DownloadEvent {
+    Requested {
+        id: u64,
+        url: String,
+        suggested_filename: String,
+    },
+    Started {
+        id: u64,
+        url: String,
+        path: String,
+    },
+    Progress {
+        id: u64,
+        received: u64,
+        total: u64,
+    },
+    Completed {
+        id: u64,
+        path: Option<String>,
+        received: u64,
+        error: Option<String>,
+    },
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::DownloadEvent;
+    use crate::UserEvents;
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::rc::Rc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{Duration, Instant};
+    use webkit2gtk::{
+        glib::{self, ObjectExt, UserDirectory},
+        Download, DownloadExt, URIRequestExt, URIResponseExt, WebContextExt, WebViewExt,
+    };
+    use wry::{
+        application::{event_loop::EventLoopProxy, window::WindowId},
+        webview::{WebView, WebviewExtUnix},
+    };
+
+    const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+    enum Destination {
+        Undecided,
+        Accepted(PathBuf),
+        Denied,
+    }
+
+    // WebKitGTK before 2.40 needs the destination before "decide-destination"
+    // returns, while Node answers later. Downloads go to a staging file next to
+    // the user's downloads until Node has decided, like browsers do while their
+    // save dialog is open.
+    struct Pending {
+        download: Download,
+        url: String,
+        staging: PathBuf,
+        destination: Destination,
+        // the error WebKit reported, once the download has finished
+        finished: Option<Option<String>>,
+        send: Rc<dyn Fn(DownloadEvent)>,
+    }
+
+    thread_local! {
+        // downloads Node hasn't decided on yet, or that are still running
+        static PENDING: RefCell<HashMap<u64, Pending>> = RefCell::new(HashMap::new());
+    }
+
+    pub(crate) fn connect(
+        webview: &WebView,
+        window_id: WindowId,
+        proxy: EventLoopProxy<UserEvents>,
+    ) {
+        let webkit_webview = webview.webview();
+        let context = match webkit_webview.context() {
+            Some(context) => context,
+            None => return,
+        };
+        // child webviews share the context, only pick up the window's own downloads
+        let target = webkit_webview.downgrade();
+        context.connect_download_started(move |_, download| {
+            let target = target.upgrade();
+            if target.is_none() || download.web_view() != target {
+                return;
+            }
+            let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+            let proxy = proxy.clone();
+            connect_download(download, id, staging_dir(), move |event| {
+                let _ = proxy.send_event(UserEvents::Download(window_id, event));
+            });
+        });
+    }
+
+    fn staging_dir() -> PathBuf {
+        glib::user_special_dir(UserDirectory::Downloads).unwrap_or_else(std::env::temp_dir)
+    }
+
+    fn connect_download<F: Fn(DownloadEvent) + 'static>(
+        download: &Download,
+        id: u64,
+        staging_dir: PathBuf,
+        send: F,
+    ) {
+        let url = download
+            .request()
+            .and_then(|request| request.uri())
+            .map(|uri| uri.to_string())
+            .unwrap_or_default();
+        let send: Rc<dyn Fn(DownloadEvent)> = Rc::new(send);
+
+        let decide_send = send.clone();
+        download.connect_decide_destination(move |download, suggested_filename| {
+            let staging =
+                staging_dir.join(format!(".webarea-{}-{}.download", std::process::id(), id));
+            match glib::filename_to_uri(&staging, None) {
+                Ok(uri) => download.set_destination(&uri),
+                // reported as a failed download once WebKit finishes it
+                Err(_) => {
+                    download.cancel();
+                    return true;
+                }
+            }
+            PENDING.with(|pending| {
+                pending.borrow_mut().insert(
+                    id,
+                    Pending {
+                        download: download.clone(),
+                        url: url.clone(),
+                        staging,
+                        destination: Destination::Undecided,
+                        finished: None,
+                        send: decide_send.clone(),
+                    },
+                )
+            });
+            decide_send(DownloadEvent::Requested {
+                id,
+                url: url.clone(),
+                suggested_filename: suggested_filename.to_string(),
+            });
+            true
+        });
+
+        // progress is only reported once Node has accepted the download
+        let progress_send = send.clone();
+        let last_progress = Cell::new(Instant::now());
+        download.connect_received_data(move |download, _| {
+            let accepted = PENDING.with(|pending| {
+                pending
+                    .borrow()
+                    .get(&id)
+                    .is_some_and(|pending| matches!(pending.destination, Destination::Accepted(_)))
+            });
+            if !accepted || last_progress.get().elapsed() < PROGRESS_INTERVAL {
+                return;
+            }
+            last_progress.set(Instant::now());
+            progress_send(DownloadEvent::Progress {
+                id,
+                received: download.received_data_length(),
+                total: total_length(download),
+            });
+        });
+
+        // "failed" is always followed by "finished"
+        let error = Rc::new(RefCell::new(None));
+        let failed_error = error.clone();
+        download.connect_failed(move |_, err| {
+            *failed_error.borrow_mut() = Some(err.to_string());
+        });
+        download.connect_finished(move |download| {
+            let error = error.borrow_mut().take();
+            let staged = PENDING.with(|pending| match pending.borrow_mut().get_mut(&id) {
+                Some(pending) => {
+                    pending.finished = Some(error.clone());
+                    true
+                }
+                None => false,
+            });
+            if staged {
+                complete(id);
+            } else {
+                send(DownloadEvent::Completed {
+                    id,
+                    path: None,
+                    received: download.received_data_length(),
+                    error,
+                });
+            }
+        });
+    }
+
+    pub(crate) fn resolve(id: u64, path: Option<String>) -> Result<(), String> {
+        let (download, send, url) = PENDING.with(|pending| {
+            let mut pending = pending.borrow_mut();
+            let pending = pending
+                .get_mut(&id)
+                .filter(|pending| matches!(pending.destination, Destination::Undecided))
+                .ok_or_else(|| format!("download {} is not waiting for a destination", id))?;
+            pending.destination = match &path {
+                Some(path) => Destination::Accepted(PathBuf::from(path)),
+                None => Destination::Denied,
+            };
+            Ok::<_, String>((
+                pending.download.clone(),
+                pending.send.clone(),
+                pending.url.clone(),
+            ))
+        })?;
+        // cancelling finishes the download right away, which needs `PENDING` again
+        match path {
+            Some(path) => send(DownloadEvent::Started { id, url, path }),
+            None => download.cancel(),
+        }
+        complete(id);
+        Ok(())
+    }
+
+    // Moves the staging file into place once the download has both finished and
+    // been decided on.
+    fn complete(id: u64) {
+        let pending = PENDING.with(|pending| {
+            let mut pending = pending.borrow_mut();
+            let done = pending.get(&id).is_some_and(|pending| {
+                pending.finished.is_some() && !matches!(pending.destination, Destination::Undecided)
+            });
+            if done {
+                pending.remove(&id)
+            } else {
+                None
+            }
+        });
+        let pending = match pending {
+            Some(pending) => pending,
+            None => return,
+        };
+        let received = pending.download.received_data_length();
+        let error = pending.finished.flatten();
+        let (path, error) = match (pending.destination, error) {
+            (Destination::Accepted(destination), None) => {
+                match move_file(&pending.staging, &destination) {
+                    Ok(()) => (Some(destination.to_string_lossy().into_owned()), None),
+                    Err(err) => (None, Some(err.to_string())),
+                }
+            }
+            (Destination::Accepted(_), Some(error)) => (None, Some(error)),
+            _ => (None, Some("the download was denied".to_string())),
+        };
+        let _ = fs::remove_file(&pending.staging);
+        (pending.send)(DownloadEvent::Completed {
+            id,
+            path,
+            received,
+            error,
+        });
+    }
+
+    fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+        // renaming fails across file systems
+        if fs::rename(from, to).is_err() {
+            fs::copy(from, to)?;
+        }
+        Ok(())
+    }
+
+    fn total_length(download: &Download) -> u64 {
+        download
+            .response()
+            .map(|response| response.content_length())
+            .unwrap_or(0)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::TcpListener;
+        use std::thread;
+        use webkit2gtk::WebContext;
+
+        const BODY_CHUNKS: usize = 8;
+        const CHUNK_SIZE: usize = 32 * 1024;
+        const TIMEOUT: Duration = Duration::from_secs(20);
+
+        type Events = Rc<RefCell<Vec<DownloadEvent>>>;
+
+        // Serves the body slowly enough for progress to be reported, as
+        // `/fixture.bin` on a free port.
+        fn serve(body: Vec<u8>) -> String {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}/fixture.bin", listener.local_addr().unwrap());
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let mut reader = BufReader::new(stream);
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+                        line.clear();
+                    }
+                    let mut stream = reader.into_inner();
+                    let head = format!(
+                        "HTTP/1.1 200 OK\r\n\
+                         Content-Type: application/octet-stream\r\n\
+                         Content-Length: {}\r\n\
+                         Connection: close\r\n\r\n",
+                        body.len()
+                    );
+                    let _ = stream.write_all(head.as_bytes());
+                    for chunk in body.chunks(CHUNK_SIZE) {
+                        let _ = stream.write_all(chunk);
+                        thread::sleep(Duration::from_millis(50));
+                    }
+                }
+            });
+            url
+        }
+
+        fn start(context: &WebContext, url: &str, id: u64, staging: PathBuf) -> Events {
+            let events = Events::default();
+            let download = context.download_uri(url).unwrap();
+            let sink = events.clone();
+            connect_download(&download, id, staging, move |event| {
+                sink.borrow_mut().push(event)
+            });
+            events
+        }
+
+        fn wait_until(done: impl Fn() -> bool) {
+            let started = Instant::now();
+            while !done() {
+                assert!(started.elapsed() < TIMEOUT, "the download got stuck");
+                glib::MainContext::default().iteration(false);
+                thread::sleep(Duration::from_millis(5));
+            }
+        }
+
+        fn wait_for(events: &Events, done: fn(&DownloadEvent) -> bool) {
+            wait_until(|| events.borrow().iter().any(done));
+        }
+
+        fn requested(event: &DownloadEvent) -> bool {
+            matches!(event, DownloadEvent::Requested { .. })
+        }
+
+        fn completed(event: &DownloadEvent) -> bool {
+            matches!(event, DownloadEvent::Completed { .. })
+        }
+
+        // GTK stays on the thread it was started on, so both outcomes are
+        // checked in one test.
+        #[test]
+        #[ignore = "needs a display, run with --ignored under xvfb-run"]
+        fn downloads_wait_for_node_to_accept_or_deny_them() {
+            gtk::init().unwrap();
+            let body: Vec<u8> = (0..BODY_CHUNKS * CHUNK_SIZE).map(|i| i as u8).collect();
+            let url = serve(body.clone());
+            let context = WebContext::new_ephemeral();
+            let dir = std::env::temp_dir().join(format!("webarea-download-{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            let path = dir.join("accepted.bin");
+            let path_string = path.to_string_lossy().into_owned();
+
+            let accepted = start(&context, &url, 1, dir.clone());
+            wait_for(&accepted, requested);
+            resolve(1, Some(path_string.clone())).unwrap();
+            wait_for(&accepted, completed);
+            let events = accepted.borrow();
+            match &events[0] {
+                DownloadEvent::Requested {
+                    id: 1,
+                    url: requested_url,
+                    suggested_filename,
+                } => {
+                    assert_eq!(requested_url, &url);
+                    assert_eq!(suggested_filename, "fixture.bin");
+                }
+                _ => panic!("the download has to be requested first"),
+            }
+            assert!(events.iter().any(|event| match event {
+                DownloadEvent::Started { id: 1, path, .. } => *path == path_string,
+                _ => false,
+            }));
+            let progress: Vec<(u64, u64)> = events
+                .iter()
+                .filter_map(|event| match event {
+                    DownloadEvent::Progress {
+                        id: 1,
+                        received,
+                        total,
+                    } => Some((*received, *total)),
+                    _ => None,
+                })
+                .collect();
+            assert!(!progress.is_empty());
+            for (received, total) in progress {
+                assert_eq!(total, body.len() as u64);
+                assert!(received <= total);
+            }
+            match events.last().unwrap() {
+                DownloadEvent::Completed {
+                    id: 1,
+                    path: Some(completed_path),
+                    received,
+                    error: None,
+                } => {
+                    assert_eq!(*completed_path, path_string);
+                    assert_eq!(*received, body.len() as u64);
+                }
+                _ => panic!("the download has to complete without an error"),
+            }
+            assert_eq!(std::fs::read(&path).unwrap(), body);
+
+            let denied = start(&context, &url, 2, dir.clone());
+            wait_for(&denied, requested);
+            resolve(2, None).unwrap();
+            wait_for(&denied, completed);
+            assert!(matches!(
+                denied.borrow().last(),
+                Some(DownloadEvent::Completed {
+                    id: 2,
+                    error: Some(_),
+                    ..
+                })
+            ));
+            // resolving twice is refused, nothing waits for it anymore
+            assert!(resolve(2, None).is_err());
+
+            // accepting after WebKit finished still moves the file into place
+            let late = start(&context, &url, 3, dir.clone());
+            wait_for(&late, requested);
+            wait_until(|| PENDING.with(|pending| pending.borrow()[&3].finished.is_some()));
+            let late_path = dir.join("late.bin");
+            resolve(3, Some(late_path.to_string_lossy().into_owned())).unwrap();
+            assert!(matches!(
+                late.borrow().last(),
+                Some(DownloadEvent::Completed {
+                    id: 3,
+                    path: Some(_),
+                    error: None,
+                    ..
+                })
+            ));
+            assert_eq!(std::fs::read(&late_path).unwrap(), body);
+
+            // no staging files are left behind
+            let files = std::fs::read_dir(&dir).unwrap().count();
+            let _ = std::fs::remove_dir_all(&dir);
+            assert_eq!(files, 2);
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use crate::UserEvents;
+    use wry::{
+        application::{event_loop::EventLoopProxy, window::WindowId},
+        webview::WebView,
+    };
+
+    // wry doesn't expose download hooks on this platform yet
+    pub(crate) fn connect(
+        _webview: &WebView,
+        _window_id: WindowId,
+        _proxy: EventLoopProxy<UserEvents>,
+    ) {
+    }
+
+    pub(crate) fn resolve(_id: u64, _path: Option<String>) -> Result<(), String> {
+        Err("downloads are not supported on this platform".to_string())
+    }
+}
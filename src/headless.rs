@@ -0,0 +1,73 @@
+pub use platform::ensure_display;
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::path::Path;
+    use std::process::{Command, Stdio};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    const FIRST_DISPLAY: u32 = 99;
+    const LAST_DISPLAY: u32 = 199;
+    const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+    // Headless windows still need a display to be created on. An existing one,
+    // like Xvfb started by the CI job, is used as is, otherwise the library
+    // starts its own Xvfb. Has to run before GTK is initialized.
+    pub fn ensure_display() -> Result<(), String> {
+        let has_display = |name| std::env::var_os(name).is_some_and(|value| !value.is_empty());
+        if has_display("DISPLAY") || has_display("WAYLAND_DISPLAY") {
+            return Ok(());
+        }
+        let display = (FIRST_DISPLAY..=LAST_DISPLAY)
+            .find(|display| !display_in_use(*display))
+            .ok_or_else(|| "no free display number for Xvfb".to_string())?;
+        // -terminate stops it once our connection goes away, the event loop
+        // thread never returns so it can't be stopped from here
+        let mut xvfb = Command::new("Xvfb")
+            .arg(format!(":{}", display))
+            .args(&[
+                "-screen",
+                "0",
+                "1920x1080x24",
+                "-nolisten",
+                "tcp",
+                "-terminate",
+            ])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| format!("could not start Xvfb: {}", err))?;
+        let started = Instant::now();
+        while !socket_path(display).exists() {
+            if let Ok(Some(status)) = xvfb.try_wait() {
+                return Err(format!("Xvfb exited with {}", status));
+            }
+            if started.elapsed() > STARTUP_TIMEOUT {
+                let _ = xvfb.kill();
+                return Err("Xvfb did not start in time".to_string());
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        std::env::set_var("DISPLAY", format!(":{}", display));
+        std::env::set_var("GDK_BACKEND", "x11");
+        Ok(())
+    }
+
+    fn display_in_use(display: u32) -> bool {
+        socket_path(display).exists() || Path::new(&format!("/tmp/.X{}-lock", display)).exists()
+    }
+
+    fn socket_path(display: u32) -> std::path::PathBuf {
+        Path::new("/tmp/.X11-unix").join(format!("X{}", display))
+    }
+}
+
+// Hidden windows don't need a display here.
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    pub fn ensure_display() -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use wry::{application::window::WindowId, webview::WebView};
+
+#[cfg(not(target_os = "linux"))]
+pub use platform::with_scripts;
+
+pub struct InitScript {
+    pub source: String,
+    // main frame only when false
+    pub all_frames: bool,
+}
+
+// Scripts installed into every window, they're picked up on the next
+// navigation and uninstalled again when the window closes.
+pub struct InitScripts {
+    next_id: u64,
+    windows: HashMap<WindowId, HashMap<u64, platform::Installed>>,
+}
+
+impl InitScripts {
+    pub fn new() -> Self {
+        InitScripts {
+            next_id: 1,
+            windows: HashMap::new(),
+        }
+    }
+
+    pub fn add(
+        &mut self,
+        window_id: WindowId,
+        webview: &WebView,
+        script: &InitScript,
+    ) -> Result<u64, String> {
+        let installed = platform::install(webview, script)?;
+        let id = self.next_id;
+        self.next_id += 1;
+        self.windows
+            .entry(window_id)
+            .or_default()
+            .insert(id, installed);
+        Ok(id)
+    }
+
+    pub fn remove(
+        &mut self,
+        window_id: WindowId,
+        webview: &WebView,
+        id: u64,
+    ) -> Result<(), String> {
+        let installed = self
+            .windows
+            .get_mut(&window_id)
+            .and_then(|scripts| scripts.remove(&id))
+            .ok_or_else(|| format!("no initialization script with id {}", id))?;
+        platform::uninstall(webview, &installed);
+        Ok(())
+    }
+
+    pub fn release(&mut self, window_id: &WindowId, webview: &WebView) {
+        if let Some(scripts) = self.windows.remove(window_id) {
+            for installed in scripts.values() {
+                platform::uninstall(webview, installed);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::InitScript;
+    use webkit2gtk::{
+        UserContentInjectedFrames, UserContentManagerExt, UserScript, UserScriptInjectionTime,
+        WebViewExt,
+    };
+    use wry::webview::{WebView, WebviewExtUnix};
+
+    pub type Installed = UserScript;
+
+    // every window has a context, and so a content manager, of its own
+    pub fn install(webview: &WebView, script: &InitScript) -> Result<Installed, String> {
+        let manager = webview
+            .webview()
+            .user_content_manager()
+            .ok_or_else(|| "webview has no content manager".to_string())?;
+        let frames = if script.all_frames {
+            UserContentInjectedFrames::AllFrames
+        } else {
+            UserContentInjectedFrames::TopFrame
+        };
+        let user_script = UserScript::new(
+            &script.source,
+            frames,
+            UserScriptInjectionTime::Start,
+            &[],
+            &[],
+        );
+        manager.add_script(&user_script);
+        Ok(user_script)
+    }
+
+    pub fn uninstall(webview: &WebView, installed: &Installed) {
+        if let Some(manager) = webview.webview().user_content_manager() {
+            manager.remove_script(installed);
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::InitScript;
+    use wry::webview::{WebView, WebViewBuilder};
+
+    pub type Installed = ();
+
+    // wry only takes scripts before the webview is built here and injects
+    // them into every frame, main frame scripts are guarded instead. They run
+    // inside a block, so their top level `let` and `const` stay local.
+    pub fn with_scripts<'a>(
+        mut builder: WebViewBuilder<'a>,
+        scripts: &[InitScript],
+    ) -> WebViewBuilder<'a> {
+        for script in scripts {
+            builder = if script.all_frames {
+                builder.with_initialization_script(&script.source)
+            } else {
+                let source = format!("if (window === window.top) {{\n{}\n}}", script.source);
+                builder.with_initialization_script(&source)
+            };
+        }
+        builder
+    }
+
+    pub fn install(_webview: &WebView, _script: &InitScript) -> Result<Installed, String> {
+        Err("initialization scripts can't be changed at runtime on this platform".to_string())
+    }
+
+    pub fn uninstall(_webview: &WebView, _installed: &Installed) {}
+}
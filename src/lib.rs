@@ -1,17 +1,71 @@
+mod automation;
+mod bridge;
+mod capture;
+mod child_webview;
+mod clipboard;
+mod cookies;
+mod deep_link;
+mod devtools;
+mod dialog;
+mod download;
+mod headless;
+mod init_script;
+mod notification;
+mod print;
+mod security;
+mod single_instance;
+mod theme;
+mod web_context;
+mod window_control;
+mod window_ids;
+mod window_tree;
+mod zoom;
+
+use automation::{Command, WebDriverError};
+use capture::{CaptureCallback, CaptureFormat, CaptureRect};
+use child_webview::{Bounds, ChildIpc, ChildOptions, ChildWebviews};
+use clipboard::{ClipboardImage, SystemClipboard};
+use cookies::{Cookie, CookieResultCallback, CookiesCallback};
+use deep_link::Registration;
+use devtools::Devtools;
+use dialog::{MessageBoxOptions, MessageBoxType};
+use download::DownloadEvent;
+#[cfg(target_os = "linux")]
+use gtk::prelude::*;
+use init_script::{InitScript, InitScripts};
 use neon::{prelude::*, types::buffer::TypedArray};
+use notification::{NotificationEvent, NotificationOptions};
+use print::{Margins, PageSize, PdfCallback, PdfOptions, PrintCallback, PrintOptions};
+use security::{PageUrl, RateLimited, RateLimiter, SecurityPolicy, Violation};
 use std::collections::HashMap;
 use std::error;
+use std::path::PathBuf;
 use std::{ops::Deref, sync::Arc};
+use theme::{ThemePreference, Themes};
+use web_context::{ClearDataCallback, DataUsageCallback, Partition, StorageKind, WebContexts};
+#[cfg(target_os = "linux")]
+use webkit2gtk::WebViewExt;
+use window_control::WindowControl;
+use window_ids::{WindowIds, WindowInfo};
+use window_tree::WindowTree;
+#[cfg(target_os = "linux")]
+use wry::application::platform::unix::{EventLoopExtUnix, WindowExtUnix};
+#[cfg(target_os = "windows")]
+use wry::application::platform::windows::EventLoopExtWindows;
+#[cfg(target_os = "linux")]
+use wry::webview::WebviewExtUnix;
 use wry::{
     application::{
         dpi::{LogicalPosition, LogicalSize, Size},
         event::{Event, StartCause, WindowEvent},
         event_loop::{ControlFlow, EventLoop, EventLoopProxy, EventLoopWindowTarget},
-        platform::windows::EventLoopExtWindows,
-        window::{Icon, Window, WindowBuilder, WindowId},
+        window::{Icon, Theme, Window, WindowBuilder, WindowId},
     },
-    webview::{WebContext, WebView, WebViewBuilder},
+    webview::{FileDropEvent, WebView, WebViewBuilder},
 };
+use zoom::Zooms;
+
+const UNSAFE_QUIT: &str = "unsafe quit";
 
 enum Error {
     WebviewNotFound,
@@ -26,18 +80,67 @@ enum UserEvents {
     SetVisibleWindow(WindowId, bool, Root<JsFunction>),
     SetResizableWindow(WindowId, bool, Root<JsFunction>),
     EvaluateScript(WindowId, String, Root<JsFunction>),
+    AddInitScript(WindowId, InitScript, Root<JsFunction>),
+    RemoveInitScript(WindowId, u64, Root<JsFunction>),
     SetWindowSize(WindowId, u32, u32, Root<JsFunction>),
     GetWindowSize(WindowId, Root<JsFunction>),
     SetMinimizedWindow(WindowId, bool, Root<JsFunction>),
-    DragWindow(WindowId),
+    WindowControl(WindowId, WindowControl),
     FocusWindow(WindowId, Root<JsFunction>),
     SetAlwaysOnTopWindow(WindowId, bool, Root<JsFunction>),
     SetIgnoreCursorEvents(WindowId, bool, Root<JsFunction>),
     OpenDevtools(WindowId, Root<JsFunction>),
     CloseDevtools(WindowId, Root<JsFunction>),
+    ToggleDevtools(WindowId, Root<JsFunction>),
+    SetZoom(WindowId, f64, Root<JsFunction>),
+    GetZoom(WindowId, Root<JsFunction>),
+    GetTheme(WindowId, Root<JsFunction>),
+    // GTK's theme settings changed, other platforms report it per window
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    ThemeChanged(Theme),
+    ZoomChanged(WindowId, f64),
+    CapturePage(
+        WindowId,
+        Option<CaptureRect>,
+        CaptureFormat,
+        Root<JsFunction>,
+    ),
+    AddChildWebview(WindowId, ChildOptions, Root<JsFunction>),
+    SetChildWebviewBounds(WindowId, u64, Bounds, Root<JsFunction>),
+    RemoveChildWebview(WindowId, u64, Root<JsFunction>),
+    EvaluateChildScript(WindowId, u64, String, Root<JsFunction>),
+    ChildIpc(WindowId, u64, String),
+    Print(WindowId, PrintOptions, Root<JsFunction>),
+    PrintToPdf(WindowId, PdfOptions, Root<JsFunction>),
+    IsDevtoolsOpen(WindowId, Root<JsFunction>),
+    Devtools(WindowId, bool),
     SetFramelessWindow(WindowId, bool, Root<JsFunction>),
     SetWindowIcon(WindowId, Vec<u8>, u32, u32, Root<JsFunction>),
+    ShowMessageBox(MessageBoxOptions, Option<WindowId>, Root<JsFunction>),
+    ClipboardReadText(Root<JsFunction>),
+    ClipboardWriteText(String, Root<JsFunction>),
+    ClipboardWriteHtml(String, Option<String>, Root<JsFunction>),
+    ClipboardReadImage(Root<JsFunction>),
+    ClipboardWriteImage(ClipboardImage, Root<JsFunction>),
+    ClipboardFormats(Root<JsFunction>),
+    ListWindows(Root<JsFunction>),
+    GetWindowByLabel(String, Root<JsFunction>),
+    ShowNotification(NotificationOptions, Root<JsFunction>),
+    Notification(NotificationEvent),
     IpcPostMessage(WindowId, String),
+    Automation(Command, automation::Reply),
+    AutomationReply(WindowId, String),
+    // argv and working directory of a later instance
+    SecondInstance(Vec<String>, String),
+    SecurityViolation(WindowId, Violation, String, Option<String>),
+    FileDrop(WindowId, FileDropEvent, Option<(f64, f64)>),
+    Download(WindowId, DownloadEvent),
+    ResolveDownload(u64, Option<String>, Root<JsFunction>),
+    ClearData(Option<String>, Vec<StorageKind>, Root<JsFunction>),
+    GetDataUsage(Option<String>, Root<JsFunction>),
+    GetCookies(Option<String>, String, Root<JsFunction>),
+    SetCookie(Option<String>, Cookie, Root<JsFunction>),
+    RemoveCookie(Option<String>, String, String, Root<JsFunction>),
     OnError(Error),
 }
 
@@ -50,7 +153,20 @@ struct Options {
     height: u32,
     visible: bool,
     resizable: bool,
-    initialization_script: String,
+    initialization_scripts: Vec<InitScript>,
+    file_drop: bool,
+    file_drop_prevent_default: bool,
+    downloads: bool,
+    partition: Partition,
+    security: SecurityPolicy,
+    assets_dir: Option<PathBuf>,
+    zoom: f64,
+    zoom_hotkeys: bool,
+    parent: Option<WindowId>,
+    // blocks input to the parent until closed
+    modal: bool,
+    label: Option<String>,
+    theme: ThemePreference,
 }
 
 struct IpcBoxed {
@@ -74,60 +190,409 @@ fn resolve_node_promise(channel: Channel, cb: Root<JsFunction>) {
         .join();
 }
 
+fn resolve_node_bool(channel: Channel, cb: Root<JsFunction>, value: bool) {
+    channel.send(move |mut cx| {
+        let this = cx.undefined();
+        let callback = cb.into_inner(&mut cx);
+        let value = cx.boolean(value);
+        let _ = callback.call(&mut cx, this, &[value.upcast()]);
+        Ok(())
+    });
+}
+
+fn resolve_node_result(channel: Channel, cb: Root<JsFunction>, result: Result<(), String>) {
+    channel.send(move |mut cx| {
+        let this = cx.undefined();
+        let callback = cb.into_inner(&mut cx);
+        let error = match result {
+            Ok(()) => cx.null().upcast::<JsValue>(),
+            Err(message) => cx.string(message).upcast(),
+        };
+        let _ = callback.call(&mut cx, this, &[error]);
+        Ok(())
+    });
+}
+
+fn cookie_to_js<'a, C: Context<'a>>(cx: &mut C, cookie: Cookie) -> JsResult<'a, JsObject> {
+    let object = cx.empty_object();
+    let name = cx.string(cookie.name);
+    object.set(cx, "name", name)?;
+    let value = cx.string(cookie.value);
+    object.set(cx, "value", value)?;
+    let domain = cx.string(cookie.domain);
+    object.set(cx, "domain", domain)?;
+    let path = cx.string(cookie.path);
+    object.set(cx, "path", path)?;
+    let expires = match cookie.expires {
+        Some(expires) => cx.number(expires).upcast::<JsValue>(),
+        None => cx.null().upcast(),
+    };
+    object.set(cx, "expires", expires)?;
+    let secure = cx.boolean(cookie.secure);
+    object.set(cx, "secure", secure)?;
+    let http_only = cx.boolean(cookie.http_only);
+    object.set(cx, "httpOnly", http_only)?;
+    let same_site = match cookie.same_site {
+        Some(same_site) => cx.string(same_site).upcast::<JsValue>(),
+        None => cx.null().upcast(),
+    };
+    object.set(cx, "sameSite", same_site)?;
+    Ok(object)
+}
+
+fn cookie_from_js<'a, C: Context<'a>>(
+    cx: &mut C,
+    object: Handle<'a, JsObject>,
+) -> NeonResult<Cookie> {
+    let name = object.get::<JsString, _, _>(cx, "name")?.value(cx);
+    let value = object.get::<JsString, _, _>(cx, "value")?.value(cx);
+    let domain = object.get::<JsString, _, _>(cx, "domain")?.value(cx);
+    let path = match object.get_opt::<JsString, _, _>(cx, "path")? {
+        Some(path) => path.value(cx),
+        None => "/".to_string(),
+    };
+    let expires = object
+        .get_opt::<JsNumber, _, _>(cx, "expires")?
+        .map(|expires| expires.value(cx));
+    let secure = match object.get_opt::<JsBoolean, _, _>(cx, "secure")? {
+        Some(secure) => secure.value(cx),
+        None => false,
+    };
+    let http_only = match object.get_opt::<JsBoolean, _, _>(cx, "httpOnly")? {
+        Some(http_only) => http_only.value(cx),
+        None => false,
+    };
+    let same_site = object
+        .get_opt::<JsString, _, _>(cx, "sameSite")?
+        .map(|same_site| same_site.value(cx));
+    Ok(Cookie {
+        name,
+        value,
+        domain,
+        path,
+        expires,
+        secure,
+        http_only,
+        same_site,
+    })
+}
+
+fn window_info_to_js<'a, C: Context<'a>>(cx: &mut C, info: WindowInfo) -> JsResult<'a, JsObject> {
+    let object = cx.empty_object();
+    let id = cx.number(info.id);
+    object.set(cx, "id", id)?;
+    let label = match info.label {
+        Some(label) => cx.string(label).upcast::<JsValue>(),
+        None => cx.null().upcast(),
+    };
+    object.set(cx, "label", label)?;
+    let title = cx.string(info.title);
+    object.set(cx, "title", title)?;
+    let visible = cx.boolean(info.visible);
+    object.set(cx, "visible", visible)?;
+    let focused = cx.boolean(info.focused);
+    object.set(cx, "focused", focused)?;
+    let bounds = cx.empty_object();
+    let x = cx.number(info.x);
+    bounds.set(cx, "x", x)?;
+    let y = cx.number(info.y);
+    bounds.set(cx, "y", y)?;
+    let width = cx.number(info.width);
+    bounds.set(cx, "width", width)?;
+    let height = cx.number(info.height);
+    bounds.set(cx, "height", height)?;
+    object.set(cx, "bounds", bounds)?;
+    Ok(object)
+}
+
+// urls the app was started with or that a later instance handed over
+fn send_open_urls(channel: &Channel, listener_cb: Arc<Root<JsFunction>>, urls: Vec<String>) {
+    for url in urls {
+        let listener_cb = listener_cb.clone();
+        channel.send(move |mut cx| {
+            let this = cx.undefined();
+            let callback = listener_cb.to_inner(&mut cx);
+            let event = cx.string("open-url");
+            let url = cx.string(url);
+            let _ = callback.call(&mut cx, this, &[event.upcast(), url.upcast()]);
+            Ok(())
+        });
+    }
+}
+
+fn send_theme_changed(
+    channel: &Channel,
+    listener_cb: Arc<Root<JsFunction>>,
+    window: u32,
+    theme: Theme,
+) {
+    let theme = theme::theme_name(theme);
+    channel.send(move |mut cx| {
+        let this = cx.undefined();
+        let callback = listener_cb.to_inner(&mut cx);
+        let event = cx.string("theme-changed");
+        let window = cx.number(window);
+        let theme = cx.string(theme);
+        let _ = callback.call(
+            &mut cx,
+            this,
+            &[event.upcast(), window.upcast(), theme.upcast()],
+        );
+        Ok(())
+    });
+}
+
+fn partition_webviews<'a>(
+    webviews: &'a HashMap<WindowId, WebView>,
+    web_contexts: &WebContexts,
+    partition: &Option<String>,
+) -> Result<Vec<&'a WebView>, String> {
+    // the cookie stores are only reachable through webviews using the partition
+    let partition_webviews: Vec<&WebView> = web_contexts
+        .partition_windows(partition)
+        .iter()
+        .filter_map(|window_id| webviews.get(window_id))
+        .collect();
+    if !partition_webviews.is_empty() {
+        return Ok(partition_webviews);
+    }
+    Err(match partition {
+        Some(partition) => format!("no open window uses partition \"{}\"", partition),
+        None => "no open window uses the default partition".to_string(),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn cursor_position(window: &Window) -> Option<(f64, f64)> {
+    let gdk_window = window.gtk_window().window()?;
+    let pointer = gdk_window.display().default_seat()?.pointer()?;
+    let (_, x, y, _) = gdk_window.device_position(&pointer);
+    Some((x as f64, y as f64))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn cursor_position(_window: &Window) -> Option<(f64, f64)> {
+    None
+}
+
 fn create_new_window(
     options: Options,
     event_loop: &EventLoopWindowTarget<UserEvents>,
     proxy: EventLoopProxy<UserEvents>,
+    parent: Option<&Window>,
+    web_contexts: &mut WebContexts,
+    init_scripts: &mut InitScripts,
 ) -> Result<(WindowId, WebView), Box<dyn error::Error>> {
-    let window = WindowBuilder::new()
+    let builder = WindowBuilder::new()
         .with_title(options.title)
         .with_inner_size(Size::new(LogicalSize::new(options.width, options.height)))
         .with_visible(options.visible)
         .with_resizable(options.resizable)
         .with_transparent(options.transparent)
-        .with_decorations(!options.frameless)
-        .build(event_loop)?;
+        .with_decorations(!options.frameless);
+    let builder = theme::with_theme(builder, options.theme);
+    let window = window_tree::with_parent(builder, parent, options.modal)?.build(event_loop)?;
+    theme::apply(&window, options.theme);
 
     let window_id = window.id();
 
-    let handler = move |window: &Window, req: String| match req.as_str() {
-        "drag-window" => {
-            let _ = proxy.send_event(UserEvents::DragWindow(window.id()));
+    let download_proxy = proxy.clone();
+    let devtools_proxy = proxy.clone();
+    let file_drop_proxy = proxy.clone();
+    let file_drop_prevent_default = options.file_drop_prevent_default;
+    let file_drop_handler = move |window: &Window, event: FileDropEvent| {
+        let position = match event {
+            FileDropEvent::Cancelled => None,
+            _ => cursor_position(window),
+        };
+        let _ = file_drop_proxy.send_event(UserEvents::FileDrop(window.id(), event, position));
+        // returning true keeps the webview from handling the drop itself
+        file_drop_prevent_default
+    };
+
+    let page_url = PageUrl::default();
+    let handler_page_url = page_url.clone();
+    let security = options.security;
+    let csp = security.csp.clone();
+    let rate_limiter = RateLimiter::new(security.rate_limit);
+    let handler = move |window: &Window, req: String| {
+        let origin = security::origin(&handler_page_url.get());
+        let violation = |violation: Violation, origin: String, channel: Option<String>| {
+            let violation = UserEvents::SecurityViolation(window.id(), violation, origin, channel);
+            let _ = proxy.send_event(violation);
+        };
+        // frames and page scripts can reach the handler without the bridge
+        let keyed = bridge::strip_message_key(window.id(), &req);
+        // replies to automation scripts skip the policy, they only settle a
+        // script run in this window when they carry its nonce
+        if let Some(reply) = automation::parse_reply(keyed.unwrap_or(&req)) {
+            let reply = UserEvents::AutomationReply(window.id(), reply.to_string());
+            let _ = proxy.send_event(reply);
+            return;
+        }
+        // the sender's own origin is unknown, it's not necessarily the page's
+        let req = match keyed {
+            Some(req) => req,
+            None => return violation(Violation::Ipc, String::new(), None),
+        };
+        let control = WindowControl::parse(req);
+        // only asks Rust to push the window state, any page may do that
+        if let Some(WindowControl::BridgeReady) = control {
+            let ready = UserEvents::WindowControl(window.id(), WindowControl::BridgeReady);
+            let _ = proxy.send_event(ready);
+            return;
         }
-        _ if req.starts_with("ipc:") => {
-            let message = req.replace("ipc:", "");
-            let _ = proxy.send_event(UserEvents::IpcPostMessage(window.id(), message));
+        if !security.allows_ipc(&origin) {
+            let kind = match control {
+                Some(_) => Violation::DragWindow,
+                None => Violation::Ipc,
+            };
+            return violation(kind, origin, None);
+        }
+        match rate_limiter.check() {
+            RateLimited::No => {}
+            // only the first dropped message is reported, reporting every one
+            // would flood Node just the same
+            RateLimited::Started => return violation(Violation::RateLimit, origin, None),
+            RateLimited::Ongoing => return,
+        }
+        if let Some(control) = control {
+            let _ = proxy.send_event(UserEvents::WindowControl(window.id(), control));
+            return;
+        }
+        if let Some(message) = req.strip_prefix("ipc:") {
+            match security::message_channel(message) {
+                Some(channel) if security.allows_channel(&channel) => {
+                    let message = message.to_string();
+                    let _ = proxy.send_event(UserEvents::IpcPostMessage(window.id(), message));
+                }
+                channel => violation(Violation::Channel, origin, channel),
+            }
         }
-        _ => {}
     };
 
-    let data_directory = std::env::temp_dir();
-    let mut web_context = WebContext::new(Some(data_directory));
-    let webview = WebViewBuilder::new(window)?
-        // always should be fill
-        .with_initialization_script(&options.initialization_script)
+    let web_context = web_contexts.get(window_id, &options.partition)?;
+    let mut builder = WebViewBuilder::new(window)?
         .with_transparent(options.transparent)
         .with_devtools(options.devtools)
-        .with_web_context(&mut web_context)
-        .with_ipc_handler(handler)
-        .with_html("")?
-        .build()?;
+        .with_web_context(web_context)
+        .with_ipc_handler(handler);
+    if options.file_drop {
+        builder = builder.with_file_drop_handler(file_drop_handler);
+    }
+    if let Some(assets_dir) = options.assets_dir {
+        let protocol = security::ASSET_PROTOCOL.to_string();
+        builder = builder.with_custom_protocol(protocol, move |request| {
+            security::asset_response(&assets_dir, csp.as_deref(), request)
+        });
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let page_url = page_url.clone();
+        builder = builder.with_navigation_handler(move |url| {
+            page_url.navigated(url);
+            true
+        });
+    }
+    // the bridge always comes first, page scripts may rely on it
+    let bridge = bridge::bridge_script(window_id, options.frameless, options.zoom_hotkeys);
+    let mut scripts = vec![bridge];
+    scripts.extend(options.initialization_scripts);
+    #[cfg(not(target_os = "linux"))]
+    let webview = {
+        // nothing to track, the scripts can't be removed later on
+        let _ = init_scripts;
+        init_script::with_scripts(builder, &scripts)
+            .with_html("")?
+            .build()?
+    };
+    // scripts go straight to the content manager here, so the first page is
+    // only loaded once they're in place
+    #[cfg(target_os = "linux")]
+    let webview = {
+        let webview = builder.build()?;
+        for script in &scripts {
+            init_scripts.add(window_id, &webview, script)?;
+        }
+        webview.webview().load_html("", None);
+        webview
+    };
+    page_url.attach(&webview);
+    if options.downloads {
+        download::connect(&webview, window_id, download_proxy);
+    }
+    if options.devtools {
+        devtools::connect(&webview, window_id, devtools_proxy);
+    }
     Ok((window_id, webview))
 }
 
 fn app_init(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let listener_cb = cx.argument::<JsFunction>(0)?.root(&mut cx);
     let result_cb = cx.argument::<JsFunction>(1)?.root(&mut cx);
-
+    let app_id = cx.argument::<JsString>(2)?.value(&mut cx);
+    let data_dir = cx.argument::<JsValue>(3)?;
+    let devtools_allowed = cx.argument::<JsBoolean>(4)?.value(&mut cx);
+    // windows are never shown, for tests on machines without a display
+    let headless = cx.argument::<JsBoolean>(5)?.value(&mut cx);
+    // arguments with these schemes are reported as "open-url"
+    let url_schemes = cx.argument::<JsArray>(6)?;
+    let url_schemes = string_array(&mut cx, url_schemes)?;
+
+    let data_dir = match data_dir.downcast::<JsString, _>(&mut cx) {
+        Ok(data_dir) => PathBuf::from(data_dir.value(&mut cx)),
+        // every app runs as node, so only the app id tells their data apart
+        Err(_) if app_id.is_empty() => {
+            return cx.throw_error("an appId or a dataDir is needed to store the app's data")
+        }
+        Err(_) => web_context::default_data_dir(&app_id),
+    };
     let listener_cb = Arc::new(listener_cb);
     let result_cb = Arc::new(result_cb);
     let channel = cx.channel();
     std::thread::spawn(move || {
+        if headless {
+            if let Err(message) = headless::ensure_display() {
+                // the app never starts, `_init` rejects with the reason
+                channel.send(move |mut cx| {
+                    let this = cx.undefined();
+                    let callback = result_cb.to_inner(&mut cx);
+                    let message = cx.string(message);
+                    let _ = callback.call(&mut cx, this, &[message.upcast()]);
+                    Ok(())
+                });
+                return;
+            }
+        }
         let event_loop: EventLoop<UserEvents> = EventLoop::new_any_thread();
         let proxy = event_loop.create_proxy();
-        let mut webviews = HashMap::new();
+        let mut webviews: HashMap<WindowId, WebView> = HashMap::new();
+        let mut clipboard = SystemClipboard::new();
+        let mut web_contexts = WebContexts::new(data_dir);
+        let mut init_scripts = InitScripts::new();
+        let mut devtools = Devtools::new(devtools_allowed);
+        let mut zooms = Zooms::new();
+        let mut themes = Themes::new();
+        let mut automation_scripts = automation::Scripts::new();
+        let mut child_webviews = ChildWebviews::new();
+        let mut window_tree = WindowTree::new();
+        let mut window_ids = WindowIds::new();
+        let event_loop_thread = std::thread::current().id();
         std::panic::set_hook(Box::new(move |panic_info| {
-            println!("{}", panic_info);
+            eprintln!("{}", panic_info);
+            let payload = panic_info.payload();
+            let message = payload.downcast_ref::<&str>().copied().or_else(|| {
+                payload
+                    .downcast_ref::<String>()
+                    .map(|message| message.as_str())
+            });
+            let is_unsafe_quit = message == Some(UNSAFE_QUIT);
+            // dialogs can only be shown from the event loop thread, and nobody
+            // would be there to close one in headless mode
+            if std::thread::current().id() == event_loop_thread && !is_unsafe_quit && !headless {
+                let options = MessageBoxOptions::fatal_error(panic_info.to_string());
+                dialog::show_message_box_blocking(options, None);
+            }
         }));
         event_loop.run(move |event, event_loop, control_flow| {
             *control_flow = ControlFlow::Wait;
@@ -139,32 +604,75 @@ fn app_init(mut cx: FunctionContext) -> JsResult<JsUndefined> {
                     channel.send(move |mut cx| {
                         let this = cx.undefined();
                         let callback = result_cb.to_inner(&mut cx);
+                        let null = cx.null();
                         let proxy_boxed = cx.boxed(IpcBoxed {
                             proxy: Arc::new(proxy),
                         });
-                        let _ = callback.call(&mut cx, this, &[proxy_boxed.upcast()]);
+                        let _ =
+                            callback.call(&mut cx, this, &[null.upcast(), proxy_boxed.upcast()]);
                         Ok(())
                     });
+                    let urls = deep_link::urls(std::env::args(), &url_schemes);
+                    send_open_urls(&channel, listener_cb, urls);
                 }
-                Event::UserEvent(UserEvents::CreateNewWindow(option, cb)) => {
-                    let result = create_new_window(option, &event_loop, proxy.clone());
+                Event::UserEvent(UserEvents::CreateNewWindow(mut option, cb)) => {
+                    option.devtools = devtools.enabled(option.devtools);
+                    option.visible = option.visible && !headless;
+                    let devtools_enabled = option.devtools;
+                    let initial_zoom = option.zoom;
+                    let security = option.security.clone();
+                    let (parent, modal) = (option.parent, option.modal);
+                    let label = option.label.clone();
+                    let title = option.title.clone();
+                    // the parent may have been closed in the meantime
+                    let parent_window = parent
+                        .and_then(|parent| webviews.get(&parent))
+                        .map(|parent| parent.window());
+                    let result = match window_ids.check_label(&label) {
+                        Ok(()) => create_new_window(
+                            option,
+                            &event_loop,
+                            proxy.clone(),
+                            parent_window,
+                            &mut web_contexts,
+                            &mut init_scripts,
+                        ),
+                        Err(message) => Err(message.into()),
+                    };
                     match result {
                         Ok((window_id, webview)) => {
                             webviews.insert(window_id, webview);
+                            let id = window_ids.add(window_id, label, title);
+                            devtools.register(window_id, devtools_enabled);
+                            themes.add(webviews[&window_id].window(), &proxy);
+                            child_webviews.register(window_id, security);
+                            if let Some(parent) = parent.and_then(|parent| webviews.get(&parent)) {
+                                let child = webviews[&window_id].window();
+                                window_tree.add(child, parent.window(), modal);
+                            }
+                            if initial_zoom != 1.0 {
+                                zooms.set(&webviews[&window_id], window_id, initial_zoom);
+                            }
                             channel.send(move |mut cx| {
                                 let this = cx.undefined();
                                 let callback = cb.into_inner(&mut cx);
                                 let window_id_boxed = cx.boxed(WindowIdBoxed { window_id });
-                                let _ = callback.call(&mut cx, this, &[window_id_boxed.upcast()]);
+                                let id = cx.number(id);
+                                let _ = callback.call(
+                                    &mut cx,
+                                    this,
+                                    &[window_id_boxed.upcast(), id.upcast()],
+                                );
                                 Ok(())
                             });
                         }
-                        Err(_) => {
+                        Err(err) => {
+                            let message = err.to_string();
                             channel.send(move |mut cx| {
                                 let this = cx.undefined();
                                 let callback = listener_cb.to_inner(&mut cx);
                                 let event = cx.string("error");
-                                let message = cx.string("Webview not found");
+                                let message = cx.string(message);
                                 let _ = callback.call(
                                     &mut cx,
                                     this,
@@ -177,7 +685,38 @@ fn app_init(mut cx: FunctionContext) -> JsResult<JsUndefined> {
                 }
                 Event::UserEvent(UserEvents::CloseWindow(window_id, cb)) => {
                     resolve_node_promise(channel.clone(), cb);
-                    webviews.remove(&window_id);
+                    // children go first, Node only knows about the window it closed
+                    let mut closing = window_tree.descendants(&window_id);
+                    for window in closing
+                        .iter()
+                        .filter_map(|child_id| window_ids.get(child_id))
+                    {
+                        let listener_cb = listener_cb.clone();
+                        channel.send(move |mut cx| {
+                            let this = cx.undefined();
+                            let callback = listener_cb.to_inner(&mut cx);
+                            let event = cx.string("window-closed");
+                            let window = cx.number(window);
+                            let _ =
+                                callback.call(&mut cx, this, &[event.upcast(), window.upcast()]);
+                            Ok(())
+                        });
+                    }
+                    closing.push(window_id);
+                    for window_id in closing {
+                        // the parent takes input again before its modal child is gone
+                        window_tree.remove(&window_id, &webviews);
+                        if let Some(webview) = webviews.remove(&window_id) {
+                            init_scripts.release(&window_id, &webview);
+                        }
+                        web_contexts.release(&window_id);
+                        devtools.release(&window_id);
+                        zooms.release(&window_id);
+                        themes.release(&window_id);
+                        automation_scripts.release(&window_id);
+                        child_webviews.release(&window_id);
+                        window_ids.release(&window_id);
+                    }
                 }
                 Event::UserEvent(UserEvents::CenterWindow(window_id, cb)) => {
                     let webview = webviews.get(&window_id).unwrap();
@@ -197,12 +736,15 @@ fn app_init(mut cx: FunctionContext) -> JsResult<JsUndefined> {
                     let webview = webviews.get(&window_id).unwrap();
                     let window = webview.window();
                     window.set_title(&title);
+                    window_ids.set_title(&window_id, title);
                     resolve_node_promise(channel.clone(), cb);
                 }
                 Event::UserEvent(UserEvents::SetVisibleWindow(window_id, visible, cb)) => {
-                    let webview = webviews.get(&window_id).unwrap();
-                    let window = webview.window();
-                    window.set_visible(visible);
+                    if !headless {
+                        let webview = webviews.get(&window_id).unwrap();
+                        let window = webview.window();
+                        window.set_visible(visible);
+                    }
                     resolve_node_promise(channel.clone(), cb);
                 }
                 Event::UserEvent(UserEvents::SetResizableWindow(window_id, resizable, cb)) => {
@@ -216,6 +758,27 @@ fn app_init(mut cx: FunctionContext) -> JsResult<JsUndefined> {
                     let _ = webview.evaluate_script(&script);
                     resolve_node_promise(channel.clone(), cb);
                 }
+                Event::UserEvent(UserEvents::AddInitScript(window_id, script, cb)) => {
+                    let webview = webviews.get(&window_id).unwrap();
+                    let result = init_scripts.add(window_id, webview, &script);
+                    channel.send(move |mut cx| {
+                        let this = cx.undefined();
+                        let callback = cb.into_inner(&mut cx);
+                        let args = match result {
+                            Ok(id) => {
+                                vec![cx.null().upcast::<JsValue>(), cx.number(id as f64).upcast()]
+                            }
+                            Err(message) => vec![cx.string(message).upcast()],
+                        };
+                        let _ = callback.call(&mut cx, this, args);
+                        Ok(())
+                    });
+                }
+                Event::UserEvent(UserEvents::RemoveInitScript(window_id, id, cb)) => {
+                    let webview = webviews.get(&window_id).unwrap();
+                    let result = init_scripts.remove(window_id, webview, id);
+                    resolve_node_result(channel.clone(), cb, result);
+                }
                 Event::UserEvent(UserEvents::SetWindowSize(window_id, width, height, cb)) => {
                     let webview = webviews.get(&window_id).unwrap();
                     let window = webview.window();
@@ -265,18 +828,50 @@ fn app_init(mut cx: FunctionContext) -> JsResult<JsUndefined> {
                 }
                 Event::UserEvent(UserEvents::OpenDevtools(window_id, cb)) => {
                     let webview = webviews.get(&window_id).unwrap();
-                    webview.open_devtools();
+                    devtools.set_open(webview, window_id, true, &proxy);
                     resolve_node_promise(channel.clone(), cb);
                 }
                 Event::UserEvent(UserEvents::CloseDevtools(window_id, cb)) => {
                     let webview = webviews.get(&window_id).unwrap();
-                    webview.close_devtools();
+                    devtools.set_open(webview, window_id, false, &proxy);
                     resolve_node_promise(channel.clone(), cb);
                 }
+                Event::UserEvent(UserEvents::ToggleDevtools(window_id, cb)) => {
+                    let webview = webviews.get(&window_id).unwrap();
+                    let open = !devtools.is_open(webview, &window_id);
+                    let open = devtools.set_open(webview, window_id, open, &proxy);
+                    resolve_node_bool(channel.clone(), cb, open);
+                }
+                Event::UserEvent(UserEvents::IsDevtoolsOpen(window_id, cb)) => {
+                    let webview = webviews.get(&window_id).unwrap();
+                    let open = devtools.is_open(webview, &window_id);
+                    resolve_node_bool(channel.clone(), cb, open);
+                }
+                Event::UserEvent(UserEvents::Devtools(window_id, open)) => {
+                    let window = match window_ids.get(&window_id) {
+                        Some(window) => window,
+                        None => return,
+                    };
+                    channel.send(move |mut cx| {
+                        let this = cx.undefined();
+                        let callback = listener_cb.to_inner(&mut cx);
+                        let event = if open {
+                            cx.string("devtools-opened")
+                        } else {
+                            cx.string("devtools-closed")
+                        };
+                        let window = cx.number(window);
+                        let _ = callback.call(&mut cx, this, &[event.upcast(), window.upcast()]);
+                        Ok(())
+                    });
+                }
                 Event::UserEvent(UserEvents::SetFramelessWindow(window_id, flag, cb)) => {
                     let webview = webviews.get(&window_id).unwrap();
                     let window = webview.window();
                     window.set_decorations(!flag);
+                    let css_zoom = zooms.css_zoom(&window_id);
+                    let _ =
+                        webview.evaluate_script(&bridge::sync_script(window_id, flag, css_zoom));
                     resolve_node_promise(channel.clone(), cb);
                 }
                 Event::UserEvent(UserEvents::SetWindowIcon(window_id, rgba, width, height, cb)) => {
@@ -294,118 +889,918 @@ fn app_init(mut cx: FunctionContext) -> JsResult<JsUndefined> {
                     }
                 }
 
+                Event::UserEvent(UserEvents::ShowMessageBox(options, parent_id, cb)) => {
+                    let parent = parent_id
+                        .and_then(|window_id| webviews.get(&window_id))
+                        .map(|webview| webview.window());
+                    let channel = channel.clone();
+                    dialog::show_message_box(options, parent, move |index| {
+                        channel.send(move |mut cx| {
+                            let this = cx.undefined();
+                            let callback = cb.into_inner(&mut cx);
+                            let index = cx.number(index as f64);
+                            let _ = callback.call(&mut cx, this, &[index.upcast()]);
+                            Ok(())
+                        });
+                    });
+                }
+                Event::UserEvent(UserEvents::ClipboardReadText(cb)) => {
+                    let text = clipboard.read_text();
+                    channel.send(move |mut cx| {
+                        let this = cx.undefined();
+                        let callback = cb.into_inner(&mut cx);
+                        let text = match text {
+                            Some(text) => cx.string(text).upcast::<JsValue>(),
+                            None => cx.null().upcast(),
+                        };
+                        let _ = callback.call(&mut cx, this, &[text]);
+                        Ok(())
+                    });
+                }
+                Event::UserEvent(UserEvents::ClipboardWriteText(text, cb)) => {
+                    let result = clipboard.write_text(text);
+                    resolve_node_result(channel.clone(), cb, result);
+                }
+                Event::UserEvent(UserEvents::ClipboardWriteHtml(html, alt_text, cb)) => {
+                    let result = clipboard.write_html(html, alt_text);
+                    resolve_node_result(channel.clone(), cb, result);
+                }
+                Event::UserEvent(UserEvents::ClipboardReadImage(cb)) => {
+                    let image = clipboard.read_image();
+                    channel.send(move |mut cx| {
+                        let this = cx.undefined();
+                        let callback = cb.into_inner(&mut cx);
+                        match image {
+                            Some(image) => {
+                                let mut data = cx.buffer(image.rgba.len())?;
+                                data.as_mut_slice(&mut cx).copy_from_slice(&image.rgba);
+                                let width = cx.number(image.width as f64);
+                                let height = cx.number(image.height as f64);
+                                let _ = callback.call(
+                                    &mut cx,
+                                    this,
+                                    &[data.upcast(), width.upcast(), height.upcast()],
+                                );
+                            }
+                            None => {
+                                let data = cx.null();
+                                let _ = callback.call(&mut cx, this, &[data.upcast()]);
+                            }
+                        }
+                        Ok(())
+                    });
+                }
+                Event::UserEvent(UserEvents::ClipboardWriteImage(image, cb)) => {
+                    let result = clipboard.write_image(image);
+                    resolve_node_result(channel.clone(), cb, result);
+                }
+                Event::UserEvent(UserEvents::ClipboardFormats(cb)) => {
+                    let formats = clipboard.formats();
+                    channel.send(move |mut cx| {
+                        let this = cx.undefined();
+                        let callback = cb.into_inner(&mut cx);
+                        let array = cx.empty_array();
+                        for (index, format) in formats.into_iter().enumerate() {
+                            let format = cx.string(format);
+                            array.set(&mut cx, index as u32, format)?;
+                        }
+                        let _ = callback.call(&mut cx, this, &[array.upcast()]);
+                        Ok(())
+                    });
+                }
+                Event::UserEvent(UserEvents::ListWindows(cb)) => {
+                    let mut windows: Vec<WindowInfo> = webviews
+                        .iter()
+                        .filter_map(|(window_id, webview)| {
+                            window_ids.info(window_id, webview.window())
+                        })
+                        .collect();
+                    windows.sort_by_key(|info| info.id);
+                    channel.send(move |mut cx| {
+                        let this = cx.undefined();
+                        let callback = cb.into_inner(&mut cx);
+                        let array = cx.empty_array();
+                        for (index, info) in windows.into_iter().enumerate() {
+                            let info = window_info_to_js(&mut cx, info)?;
+                            array.set(&mut cx, index as u32, info)?;
+                        }
+                        let _ = callback.call(&mut cx, this, &[array.upcast()]);
+                        Ok(())
+                    });
+                }
+                Event::UserEvent(UserEvents::GetWindowByLabel(label, cb)) => {
+                    let window = window_ids.by_label(&label);
+                    channel.send(move |mut cx| {
+                        let this = cx.undefined();
+                        let callback = cb.into_inner(&mut cx);
+                        let window = match window {
+                            Some(window) => cx.number(window).upcast::<JsValue>(),
+                            None => cx.null().upcast(),
+                        };
+                        let _ = callback.call(&mut cx, this, &[window]);
+                        Ok(())
+                    });
+                }
+                Event::UserEvent(UserEvents::ShowNotification(options, cb)) => {
+                    let notification_proxy = proxy.clone();
+                    let on_event = move |event| {
+                        let _ = notification_proxy.send_event(UserEvents::Notification(event));
+                    };
+                    let result = notification::show_notification(options, on_event);
+                    channel.send(move |mut cx| {
+                        let this = cx.undefined();
+                        let callback = cb.into_inner(&mut cx);
+                        let args = match result {
+                            Ok(id) => vec![cx.null().upcast::<JsValue>(), cx.number(id).upcast()],
+                            Err(message) => vec![cx.string(message).upcast()],
+                        };
+                        let _ = callback.call(&mut cx, this, args);
+                        Ok(())
+                    });
+                }
+                Event::UserEvent(UserEvents::Notification(event)) => {
+                    channel.send(move |mut cx| {
+                        let this = cx.undefined();
+                        let callback = listener_cb.to_inner(&mut cx);
+                        let args = match event {
+                            NotificationEvent::Click(id) => vec![
+                                cx.string("notification-click").upcast::<JsValue>(),
+                                cx.number(id).upcast(),
+                            ],
+                            NotificationEvent::Action(id, action) => vec![
+                                cx.string("notification-action").upcast::<JsValue>(),
+                                cx.number(id).upcast(),
+                                cx.string(action).upcast(),
+                            ],
+                            NotificationEvent::Close(id) => vec![
+                                cx.string("notification-close").upcast::<JsValue>(),
+                                cx.number(id).upcast(),
+                            ],
+                        };
+                        let _ = callback.call(&mut cx, this, args);
+                        Ok(())
+                    });
+                }
+
                 Event::UserEvent(UserEvents::UnsafeQuit(cb)) => {
                     resolve_node_promise(channel.clone(), cb);
-                    panic!("unsafe quit");
+                    panic!("{}", UNSAFE_QUIT);
+                }
+                Event::UserEvent(UserEvents::WindowControl(window_id, control)) => {
+                    // the window may have closed while the message was queued
+                    if let Some(webview) = webviews.get(&window_id) {
+                        match control {
+                            WindowControl::Zoom(step) => {
+                                let previous = zooms.get(&window_id);
+                                let factor = zooms.step(webview, window_id, step);
+                                if factor != previous {
+                                    let changed = UserEvents::ZoomChanged(window_id, factor);
+                                    let _ = proxy.send_event(changed);
+                                }
+                            }
+                            WindowControl::BridgeReady => {
+                                let frameless = !webview.window().is_decorated();
+                                let css_zoom = zooms.css_zoom(&window_id);
+                                let script = bridge::sync_script(window_id, frameless, css_zoom);
+                                let _ = webview.evaluate_script(&script);
+                            }
+                            control => control.apply(webview.window()),
+                        }
+                    }
                 }
-                Event::UserEvent(UserEvents::DragWindow(window_id)) => {
+                Event::UserEvent(UserEvents::SetZoom(window_id, factor, cb)) => {
                     let webview = webviews.get(&window_id).unwrap();
-                    let window = webview.window();
-                    let _ = window.drag_window();
+                    let previous = zooms.get(&window_id);
+                    let factor = zooms.set(webview, window_id, factor);
+                    if factor != previous {
+                        let _ = proxy.send_event(UserEvents::ZoomChanged(window_id, factor));
+                    }
+                    resolve_node_promise(channel.clone(), cb);
                 }
-                Event::UserEvent(UserEvents::IpcPostMessage(window_id, message)) => {
+                Event::UserEvent(UserEvents::GetZoom(window_id, cb)) => {
+                    let factor = zooms.get(&window_id);
                     channel.send(move |mut cx| {
                         let this = cx.undefined();
-                        let callback = listener_cb.to_inner(&mut cx);
-                        let event = cx.string("ipc");
-                        let window_id_boxed = cx.boxed(WindowIdBoxed { window_id });
-                        let message = cx.string(message);
-                        let _ = callback.call(
-                            &mut cx,
-                            this,
-                            &[event.upcast(), window_id_boxed.upcast(), message.upcast()],
-                        );
+                        let callback = cb.into_inner(&mut cx);
+                        let factor = cx.number(factor);
+                        let _ = callback.call(&mut cx, this, &[factor.upcast()]);
                         Ok(())
                     });
                 }
-                Event::WindowEvent {
-                    event, window_id, ..
-                } => match event {
-                    WindowEvent::CloseRequested => {
-                        let _ = channel
-                            .send(move |mut cx| {
+                Event::UserEvent(UserEvents::GetTheme(window_id, cb)) => {
+                    let theme = themes.current(&window_id);
+                    channel.send(move |mut cx| {
+                        let this = cx.undefined();
+                        let callback = cb.into_inner(&mut cx);
+                        let theme = cx.string(theme);
+                        let _ = callback.call(&mut cx, this, &[theme.upcast()]);
+                        Ok(())
+                    });
+                }
+                Event::UserEvent(UserEvents::ThemeChanged(theme)) => {
+                    for window_id in themes.changed_all(theme) {
+                        if let Some(window) = window_ids.get(&window_id) {
+                            send_theme_changed(&channel, listener_cb.clone(), window, theme);
+                        }
+                    }
+                }
+                Event::UserEvent(UserEvents::CapturePage(window_id, rect, format, cb)) => {
+                    let channel = channel.clone();
+                    let callback: CaptureCallback = Box::new(move |result| {
+                        channel.send(move |mut cx| {
+                            let this = cx.undefined();
+                            let callback = cb.into_inner(&mut cx);
+                            match result {
+                                Ok(image) => {
+                                    let error = cx.null();
+                                    let mut data = cx.buffer(image.len())?;
+                                    data.as_mut_slice(&mut cx).copy_from_slice(&image);
+                                    let _ = callback.call(
+                                        &mut cx,
+                                        this,
+                                        &[error.upcast(), data.upcast()],
+                                    );
+                                }
+                                Err(message) => {
+                                    let error = cx.string(message);
+                                    let _ = callback.call(&mut cx, this, &[error.upcast()]);
+                                }
+                            }
+                            Ok(())
+                        });
+                    });
+                    match webviews.get(&window_id) {
+                        Some(webview) => capture::capture_page(webview, rect, format, callback),
+                        None => callback(Err("window is closed".to_string())),
+                    }
+                }
+                Event::UserEvent(UserEvents::AddChildWebview(window_id, options, cb)) => {
+                    let webview = webviews.get(&window_id).unwrap();
+                    let result = child_webviews.add(window_id, webview, options, proxy.clone());
+                    channel.send(move |mut cx| {
+                        let this = cx.undefined();
+                        let callback = cb.into_inner(&mut cx);
+                        let args = match result {
+                            Ok(id) => {
+                                vec![cx.null().upcast::<JsValue>(), cx.number(id as f64).upcast()]
+                            }
+                            Err(message) => vec![cx.string(message).upcast()],
+                        };
+                        let _ = callback.call(&mut cx, this, args);
+                        Ok(())
+                    });
+                }
+                Event::UserEvent(UserEvents::SetChildWebviewBounds(window_id, id, bounds, cb)) => {
+                    let result = child_webviews.set_bounds(window_id, id, bounds);
+                    resolve_node_result(channel.clone(), cb, result);
+                }
+                Event::UserEvent(UserEvents::RemoveChildWebview(window_id, id, cb)) => {
+                    let result = child_webviews.remove(window_id, id);
+                    resolve_node_result(channel.clone(), cb, result);
+                }
+                Event::UserEvent(UserEvents::EvaluateChildScript(window_id, id, script, cb)) => {
+                    let result = child_webviews.evaluate_script(window_id, id, &script);
+                    resolve_node_result(channel.clone(), cb, result);
+                }
+                Event::UserEvent(UserEvents::ChildIpc(window_id, id, message)) => {
+                    match child_webviews.ipc(&window_id, id, &message) {
+                        ChildIpc::Forward(message) => {
+                            let window = match window_ids.get(&window_id) {
+                                Some(window) => window,
+                                None => return,
+                            };
+                            channel.send(move |mut cx| {
                                 let this = cx.undefined();
                                 let callback = listener_cb.to_inner(&mut cx);
-                                let event = cx.string("close-window");
-                                let window_id_boxed = cx.boxed(WindowIdBoxed { window_id });
+                                let event = cx.string("child-ipc");
+                                let window = cx.number(window);
+                                let id = cx.number(id as f64);
+                                let message = cx.string(message);
                                 let _ = callback.call(
                                     &mut cx,
                                     this,
-                                    &[event.upcast(), window_id_boxed.upcast()],
+                                    &[
+                                        event.upcast(),
+                                        window.upcast(),
+                                        id.upcast(),
+                                        message.upcast(),
+                                    ],
                                 );
                                 Ok(())
-                            })
-                            .join();
+                            });
+                        }
+                        ChildIpc::Ignore => {}
+                        ChildIpc::Reject(kind, origin, ipc_channel) => {
+                            let violation =
+                                UserEvents::SecurityViolation(window_id, kind, origin, ipc_channel);
+                            let _ = proxy.send_event(violation);
+                        }
                     }
-                    WindowEvent::Resized(_) => {
-                        let webview = webviews.get(&window_id).unwrap();
-                        webview.resize().unwrap();
-                        let size = webview.window().inner_size();
-                        let _ = channel.send(move |mut cx| {
+                }
+                Event::UserEvent(UserEvents::Print(window_id, options, cb)) => {
+                    let channel = channel.clone();
+                    let callback: PrintCallback = Box::new(move |result| {
+                        channel.send(move |mut cx| {
                             let this = cx.undefined();
-                            let callback = listener_cb.to_inner(&mut cx);
-                            let event = cx.string("resize-window");
-                            let window_id_boxed = cx.boxed(WindowIdBoxed { window_id });
-                            let width = cx.number(size.width as f64);
-                            let height = cx.number(size.height as f64);
-                            let _ = callback.call(
-                                &mut cx,
-                                this,
-                                &[
-                                    event.upcast(),
-                                    window_id_boxed.upcast(),
-                                    width.upcast(),
-                                    height.upcast(),
-                                ],
-                            );
+                            let callback = cb.into_inner(&mut cx);
+                            match result {
+                                Ok(printed) => {
+                                    let error = cx.null();
+                                    let printed = cx.boolean(printed);
+                                    let _ = callback.call(
+                                        &mut cx,
+                                        this,
+                                        &[error.upcast(), printed.upcast()],
+                                    );
+                                }
+                                Err(message) => {
+                                    let error = cx.string(message);
+                                    let _ = callback.call(&mut cx, this, &[error.upcast()]);
+                                }
+                            }
                             Ok(())
                         });
+                    });
+                    match webviews.get(&window_id) {
+                        Some(webview) => print::print(webview, options, callback),
+                        None => callback(Err("window is closed".to_string())),
                     }
-                    WindowEvent::Moved(size) => {
-                        let _ = channel.send(move |mut cx| {
+                }
+                Event::UserEvent(UserEvents::PrintToPdf(window_id, options, cb)) => {
+                    let channel = channel.clone();
+                    let callback: PdfCallback = Box::new(move |result| {
+                        channel.send(move |mut cx| {
                             let this = cx.undefined();
-                            let callback = listener_cb.to_inner(&mut cx);
-                            let event = cx.string("move-window");
-                            let window_id_boxed = cx.boxed(WindowIdBoxed { window_id });
-                            let x = cx.number(size.x as f64);
-                            let y = cx.number(size.y as f64);
-                            let _ = callback.call(
-                                &mut cx,
-                                this,
-                                &[
-                                    event.upcast(),
-                                    window_id_boxed.upcast(),
-                                    x.upcast(),
-                                    y.upcast(),
-                                ],
-                            );
+                            let callback = cb.into_inner(&mut cx);
+                            match result {
+                                Ok(pdf) => {
+                                    let error = cx.null();
+                                    let mut data = cx.buffer(pdf.len())?;
+                                    data.as_mut_slice(&mut cx).copy_from_slice(&pdf);
+                                    let _ = callback.call(
+                                        &mut cx,
+                                        this,
+                                        &[error.upcast(), data.upcast()],
+                                    );
+                                }
+                                Err(message) => {
+                                    let error = cx.string(message);
+                                    let _ = callback.call(&mut cx, this, &[error.upcast()]);
+                                }
+                            }
                             Ok(())
                         });
+                    });
+                    match webviews.get(&window_id) {
+                        Some(webview) => print::print_to_pdf(webview, options, callback),
+                        None => callback(Err("window is closed".to_string())),
                     }
-                    _ => {}
-                },
-                _ => (),
-            }
-        });
-    });
-    Ok(cx.undefined())
-}
-
-fn create_new_window_js(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
-    let title = cx.argument::<JsString>(1)?.value(&mut cx);
-    let devtools = cx.argument::<JsBoolean>(2)?.value(&mut cx);
-    let transparent = cx.argument::<JsBoolean>(3)?.value(&mut cx);
-    let frameless = cx.argument::<JsBoolean>(4)?.value(&mut cx);
-    let width = cx.argument::<JsNumber>(5)?.value(&mut cx) as u32;
-    let height = cx.argument::<JsNumber>(6)?.value(&mut cx) as u32;
-    let visible = cx.argument::<JsBoolean>(7)?.value(&mut cx);
-    let resizable = cx.argument::<JsBoolean>(8)?.value(&mut cx);
-    let initialization_script = cx.argument::<JsString>(9)?.value(&mut cx);
-    let cb = cx.argument::<JsFunction>(10)?.root(&mut cx);
-
-    let option = Options {
-        title,
+                }
+                Event::UserEvent(UserEvents::ZoomChanged(window_id, factor)) => {
+                    let window = match window_ids.get(&window_id) {
+                        Some(window) => window,
+                        None => return,
+                    };
+                    channel.send(move |mut cx| {
+                        let this = cx.undefined();
+                        let callback = listener_cb.to_inner(&mut cx);
+                        let event = cx.string("zoom-changed");
+                        let window = cx.number(window);
+                        let factor = cx.number(factor);
+                        let _ = callback.call(
+                            &mut cx,
+                            this,
+                            &[event.upcast(), window.upcast(), factor.upcast()],
+                        );
+                        Ok(())
+                    });
+                }
+                Event::UserEvent(UserEvents::IpcPostMessage(window_id, message)) => {
+                    let window = match window_ids.get(&window_id) {
+                        Some(window) => window,
+                        None => return,
+                    };
+                    channel.send(move |mut cx| {
+                        let this = cx.undefined();
+                        let callback = listener_cb.to_inner(&mut cx);
+                        let event = cx.string("ipc");
+                        let window = cx.number(window);
+                        let message = cx.string(message);
+                        let _ = callback.call(
+                            &mut cx,
+                            this,
+                            &[event.upcast(), window.upcast(), message.upcast()],
+                        );
+                        Ok(())
+                    });
+                }
+                Event::UserEvent(UserEvents::Automation(command, reply)) => {
+                    let window = command
+                        .window()
+                        .and_then(|handle| automation::find_window(&webviews, &window_ids, handle));
+                    match (command, window) {
+                        (Command::ListWindows, _) => {
+                            let handles = automation::window_handles(&window_ids, webviews.keys());
+                            let _ = reply.send(Ok(handles));
+                        }
+                        (_, None) => {
+                            let _ = reply.send(Err(WebDriverError::no_such_window()));
+                        }
+                        (Command::Execute(_, script, args), Some((window_id, webview))) => {
+                            automation_scripts.run(window_id, webview, &script, &args, reply);
+                        }
+                        (Command::Screenshot(_), Some((_, webview))) => {
+                            let callback = automation::screenshot(reply);
+                            capture::capture_page(webview, None, CaptureFormat::Png, callback);
+                        }
+                        (Command::GetRect(_), Some((_, webview))) => {
+                            let _ = reply.send(Ok(automation::window_rect(webview.window())));
+                        }
+                        (Command::SetRect(_, rect), Some((_, webview))) => {
+                            rect.apply(webview.window());
+                            let _ = reply.send(Ok(automation::window_rect(webview.window())));
+                        }
+                        (Command::Close(_), Some((window_id, _))) => {
+                            // closed through Node like with the close button, so the
+                            // Webview there goes away as well
+                            if let Some(window) = window_ids.get(&window_id) {
+                                channel.send(move |mut cx| {
+                                    let this = cx.undefined();
+                                    let callback = listener_cb.to_inner(&mut cx);
+                                    let event = cx.string("close-window");
+                                    let window = cx.number(window);
+                                    let _ = callback.call(
+                                        &mut cx,
+                                        this,
+                                        &[event.upcast(), window.upcast()],
+                                    );
+                                    Ok(())
+                                });
+                            }
+                            let remaining = webviews.keys().filter(|id| **id != window_id);
+                            let handles = automation::window_handles(&window_ids, remaining);
+                            let _ = reply.send(Ok(handles));
+                        }
+                    }
+                }
+                Event::UserEvent(UserEvents::AutomationReply(window_id, message)) => {
+                    automation_scripts.resolve(&window_id, &message);
+                }
+                Event::UserEvent(UserEvents::SecondInstance(argv, cwd)) => {
+                    let urls = deep_link::urls(argv.clone(), &url_schemes);
+                    let open_urls_cb = listener_cb.clone();
+                    channel.send(move |mut cx| {
+                        let this = cx.undefined();
+                        let callback = listener_cb.to_inner(&mut cx);
+                        let event = cx.string("second-instance");
+                        let js_argv = cx.empty_array();
+                        for (index, arg) in argv.into_iter().enumerate() {
+                            let arg = cx.string(arg);
+                            js_argv.set(&mut cx, index as u32, arg)?;
+                        }
+                        let cwd = cx.string(cwd);
+                        let _ = callback.call(
+                            &mut cx,
+                            this,
+                            &[event.upcast(), js_argv.upcast(), cwd.upcast()],
+                        );
+                        Ok(())
+                    });
+                    send_open_urls(&channel, open_urls_cb, urls);
+                }
+                Event::UserEvent(UserEvents::SecurityViolation(
+                    window_id,
+                    violation,
+                    origin,
+                    ipc_channel,
+                )) => {
+                    let window = match window_ids.get(&window_id) {
+                        Some(window) => window,
+                        None => return,
+                    };
+                    channel.send(move |mut cx| {
+                        let this = cx.undefined();
+                        let callback = listener_cb.to_inner(&mut cx);
+                        let event = cx.string("security-violation");
+                        let window = cx.number(window);
+                        let violation = cx.string(violation.as_str());
+                        let origin = cx.string(origin);
+                        let ipc_channel = match ipc_channel {
+                            Some(ipc_channel) => cx.string(ipc_channel).upcast::<JsValue>(),
+                            None => cx.null().upcast(),
+                        };
+                        let _ = callback.call(
+                            &mut cx,
+                            this,
+                            &[
+                                event.upcast(),
+                                window.upcast(),
+                                violation.upcast(),
+                                origin.upcast(),
+                                ipc_channel,
+                            ],
+                        );
+                        Ok(())
+                    });
+                }
+                Event::UserEvent(UserEvents::FileDrop(window_id, event, position)) => {
+                    let window = match window_ids.get(&window_id) {
+                        Some(window) => window,
+                        None => return,
+                    };
+                    channel.send(move |mut cx| {
+                        let this = cx.undefined();
+                        let callback = listener_cb.to_inner(&mut cx);
+                        let (event, paths) = match event {
+                            FileDropEvent::Hovered(paths) => ("file-drop-hovered", paths),
+                            FileDropEvent::Dropped(paths) => ("file-drop-dropped", paths),
+                            FileDropEvent::Cancelled => ("file-drop-cancelled", Vec::new()),
+                            // wry may add events later
+                            _ => return Ok(()),
+                        };
+                        let event = cx.string(event);
+                        let window = cx.number(window);
+                        let js_paths = cx.empty_array();
+                        for (index, path) in paths.iter().enumerate() {
+                            let path = cx.string(path.to_string_lossy());
+                            js_paths.set(&mut cx, index as u32, path)?;
+                        }
+                        let (x, y) = match position {
+                            Some((x, y)) => {
+                                (cx.number(x).upcast::<JsValue>(), cx.number(y).upcast())
+                            }
+                            None => (cx.null().upcast(), cx.null().upcast()),
+                        };
+                        let _ = callback.call(
+                            &mut cx,
+                            this,
+                            &[event.upcast(), window.upcast(), js_paths.upcast(), x, y],
+                        );
+                        Ok(())
+                    });
+                }
+                Event::UserEvent(UserEvents::Download(window_id, event)) => {
+                    let window = match window_ids.get(&window_id) {
+                        Some(window) => window,
+                        None => return,
+                    };
+                    channel.send(move |mut cx| {
+                        let this = cx.undefined();
+                        let callback = listener_cb.to_inner(&mut cx);
+                        let window = cx.number(window);
+                        let args = match event {
+                            DownloadEvent::Requested {
+                                id,
+                                url,
+                                suggested_filename,
+                            } => vec![
+                                cx.string("download-requested").upcast::<JsValue>(),
+                                window.upcast(),
+                                cx.number(id as f64).upcast(),
+                                cx.string(url).upcast(),
+                                cx.string(suggested_filename).upcast(),
+                            ],
+                            DownloadEvent::Started { id, url, path } => vec![
+                                cx.string("download-started").upcast::<JsValue>(),
+                                window.upcast(),
+                                cx.number(id as f64).upcast(),
+                                cx.string(url).upcast(),
+                                cx.string(path).upcast(),
+                            ],
+                            DownloadEvent::Progress {
+                                id,
+                                received,
+                                total,
+                            } => vec![
+                                cx.string("download-progress").upcast::<JsValue>(),
+                                window.upcast(),
+                                cx.number(id as f64).upcast(),
+                                cx.number(received as f64).upcast(),
+                                cx.number(total as f64).upcast(),
+                            ],
+                            DownloadEvent::Completed {
+                                id,
+                                path,
+                                received,
+                                error,
+                            } => {
+                                let path = match path {
+                                    Some(path) => cx.string(path).upcast::<JsValue>(),
+                                    None => cx.null().upcast(),
+                                };
+                                let error = match error {
+                                    Some(error) => cx.string(error).upcast::<JsValue>(),
+                                    None => cx.null().upcast(),
+                                };
+                                vec![
+                                    cx.string("download-completed").upcast::<JsValue>(),
+                                    window.upcast(),
+                                    cx.number(id as f64).upcast(),
+                                    path,
+                                    cx.number(received as f64).upcast(),
+                                    error,
+                                ]
+                            }
+                        };
+                        let _ = callback.call(&mut cx, this, args);
+                        Ok(())
+                    });
+                }
+                Event::UserEvent(UserEvents::ResolveDownload(id, path, cb)) => {
+                    let result = download::resolve(id, path);
+                    resolve_node_result(channel.clone(), cb, result);
+                }
+                Event::UserEvent(UserEvents::ClearData(partition, kinds, cb)) => {
+                    let channel = channel.clone();
+                    let callback: ClearDataCallback =
+                        Box::new(move |result| resolve_node_result(channel, cb, result));
+                    web_contexts.clear_data(partition, &kinds, callback);
+                }
+                Event::UserEvent(UserEvents::GetDataUsage(partition, cb)) => {
+                    let channel = channel.clone();
+                    let callback: DataUsageCallback = Box::new(move |result| {
+                        channel.send(move |mut cx| {
+                            let this = cx.undefined();
+                            let callback = cb.into_inner(&mut cx);
+                            match result {
+                                Ok(usage) => {
+                                    let error = cx.null();
+                                    let object = cx.empty_object();
+                                    let cookies = cx.number(usage.cookies as f64);
+                                    object.set(&mut cx, "cookies", cookies)?;
+                                    let local_storage = cx.number(usage.local_storage as f64);
+                                    object.set(&mut cx, "localStorage", local_storage)?;
+                                    let cache = cx.number(usage.cache as f64);
+                                    object.set(&mut cx, "cache", cache)?;
+                                    let indexeddb = cx.number(usage.indexeddb as f64);
+                                    object.set(&mut cx, "indexeddb", indexeddb)?;
+                                    let total = cx.number(usage.total() as f64);
+                                    object.set(&mut cx, "total", total)?;
+                                    let _ = callback.call(
+                                        &mut cx,
+                                        this,
+                                        &[error.upcast(), object.upcast()],
+                                    );
+                                }
+                                Err(message) => {
+                                    let error = cx.string(message);
+                                    let _ = callback.call(&mut cx, this, &[error.upcast()]);
+                                }
+                            }
+                            Ok(())
+                        });
+                    });
+                    web_contexts.data_usage(partition, callback);
+                }
+                Event::UserEvent(UserEvents::GetCookies(partition, url, cb)) => {
+                    let channel = channel.clone();
+                    let callback: CookiesCallback = Box::new(move |result| {
+                        channel.send(move |mut cx| {
+                            let this = cx.undefined();
+                            let callback = cb.into_inner(&mut cx);
+                            match result {
+                                Ok(cookies) => {
+                                    let error = cx.null();
+                                    let array = cx.empty_array();
+                                    for (index, cookie) in cookies.into_iter().enumerate() {
+                                        let cookie = cookie_to_js(&mut cx, cookie)?;
+                                        array.set(&mut cx, index as u32, cookie)?;
+                                    }
+                                    let _ = callback.call(
+                                        &mut cx,
+                                        this,
+                                        &[error.upcast(), array.upcast()],
+                                    );
+                                }
+                                Err(message) => {
+                                    let error = cx.string(message);
+                                    let _ = callback.call(&mut cx, this, &[error.upcast()]);
+                                }
+                            }
+                            Ok(())
+                        });
+                    });
+                    match partition_webviews(&webviews, &web_contexts, &partition) {
+                        Ok(webviews) => cookies::get_cookies(webviews[0], &url, callback),
+                        Err(err) => callback(Err(err)),
+                    }
+                }
+                Event::UserEvent(UserEvents::SetCookie(partition, cookie, cb)) => {
+                    let channel = channel.clone();
+                    let callback: CookieResultCallback =
+                        Box::new(move |result| resolve_node_result(channel, cb, result));
+                    match partition_webviews(&webviews, &web_contexts, &partition) {
+                        Ok(webviews) => cookies::set_cookie(&webviews, cookie, callback),
+                        Err(err) => callback(Err(err)),
+                    }
+                }
+                Event::UserEvent(UserEvents::RemoveCookie(partition, name, url, cb)) => {
+                    let channel = channel.clone();
+                    let callback: CookieResultCallback =
+                        Box::new(move |result| resolve_node_result(channel, cb, result));
+                    match partition_webviews(&webviews, &web_contexts, &partition) {
+                        Ok(webviews) => cookies::remove_cookie(&webviews, name, &url, callback),
+                        Err(err) => callback(Err(err)),
+                    }
+                }
+                Event::WindowEvent {
+                    event, window_id, ..
+                } => match event {
+                    WindowEvent::CloseRequested => {
+                        let window = match window_ids.get(&window_id) {
+                            Some(window) => window,
+                            None => return,
+                        };
+                        let _ = channel
+                            .send(move |mut cx| {
+                                let this = cx.undefined();
+                                let callback = listener_cb.to_inner(&mut cx);
+                                let event = cx.string("close-window");
+                                let window = cx.number(window);
+                                let _ = callback.call(
+                                    &mut cx,
+                                    this,
+                                    &[event.upcast(), window.upcast()],
+                                );
+                                Ok(())
+                            })
+                            .join();
+                    }
+                    WindowEvent::Resized(_) => {
+                        let webview = webviews.get(&window_id).unwrap();
+                        webview.resize().unwrap();
+                        child_webviews.resized(&window_id, webview.window());
+                        let size = webview.window().inner_size();
+                        let window = match window_ids.get(&window_id) {
+                            Some(window) => window,
+                            None => return,
+                        };
+                        let _ = channel.send(move |mut cx| {
+                            let this = cx.undefined();
+                            let callback = listener_cb.to_inner(&mut cx);
+                            let event = cx.string("resize-window");
+                            let window = cx.number(window);
+                            let width = cx.number(size.width as f64);
+                            let height = cx.number(size.height as f64);
+                            let _ = callback.call(
+                                &mut cx,
+                                this,
+                                &[
+                                    event.upcast(),
+                                    window.upcast(),
+                                    width.upcast(),
+                                    height.upcast(),
+                                ],
+                            );
+                            Ok(())
+                        });
+                    }
+                    WindowEvent::ThemeChanged(theme) => {
+                        if !themes.changed(&window_id, theme) {
+                            return;
+                        }
+                        if let Some(window) = window_ids.get(&window_id) {
+                            send_theme_changed(&channel, listener_cb, window, theme);
+                        }
+                    }
+                    WindowEvent::Focused(focused) => {
+                        window_ids.set_focused(&window_id, focused);
+                    }
+                    WindowEvent::Moved(size) => {
+                        let window = match window_ids.get(&window_id) {
+                            Some(window) => window,
+                            None => return,
+                        };
+                        let _ = channel.send(move |mut cx| {
+                            let this = cx.undefined();
+                            let callback = listener_cb.to_inner(&mut cx);
+                            let event = cx.string("move-window");
+                            let window = cx.number(window);
+                            let x = cx.number(size.x as f64);
+                            let y = cx.number(size.y as f64);
+                            let _ = callback.call(
+                                &mut cx,
+                                this,
+                                &[event.upcast(), window.upcast(), x.upcast(), y.upcast()],
+                            );
+                            Ok(())
+                        });
+                    }
+                    _ => {}
+                },
+                _ => (),
+            }
+        });
+    });
+    Ok(cx.undefined())
+}
+
+fn string_array<'a, C: Context<'a>>(
+    cx: &mut C,
+    array: Handle<'a, JsArray>,
+) -> NeonResult<Vec<String>> {
+    let mut strings = Vec::new();
+    for value in array.to_vec(cx)? {
+        let value = value.downcast_or_throw::<JsString, _>(cx)?;
+        strings.push(value.value(cx));
+    }
+    Ok(strings)
+}
+
+fn create_new_window_js(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let title = cx.argument::<JsString>(1)?.value(&mut cx);
+    let devtools = cx.argument::<JsBoolean>(2)?.value(&mut cx);
+    let transparent = cx.argument::<JsBoolean>(3)?.value(&mut cx);
+    let frameless = cx.argument::<JsBoolean>(4)?.value(&mut cx);
+    let width = cx.argument::<JsNumber>(5)?.value(&mut cx) as u32;
+    let height = cx.argument::<JsNumber>(6)?.value(&mut cx) as u32;
+    let visible = cx.argument::<JsBoolean>(7)?.value(&mut cx);
+    let resizable = cx.argument::<JsBoolean>(8)?.value(&mut cx);
+    let initialization_scripts = cx.argument::<JsArray>(9)?;
+    let file_drop = cx.argument::<JsBoolean>(10)?.value(&mut cx);
+    let file_drop_prevent_default = cx.argument::<JsBoolean>(11)?.value(&mut cx);
+    let downloads = cx.argument::<JsBoolean>(12)?.value(&mut cx);
+    let partition = cx.argument::<JsValue>(13)?;
+    let ephemeral = cx.argument::<JsBoolean>(14)?.value(&mut cx);
+    let ipc_origins = cx.argument::<JsValue>(15)?;
+    let remote_ipc = cx.argument::<JsBoolean>(16)?.value(&mut cx);
+    let file_ipc = cx.argument::<JsBoolean>(17)?.value(&mut cx);
+    let csp = cx.argument::<JsValue>(18)?;
+    let assets_dir = cx.argument::<JsValue>(19)?;
+    let allowed_channels = cx.argument::<JsValue>(20)?;
+    let denied_channels = cx.argument::<JsValue>(21)?;
+    let rate_limit = cx.argument::<JsValue>(22)?;
+    let zoom = cx.argument::<JsNumber>(23)?.value(&mut cx);
+    let zoom_hotkeys = cx.argument::<JsBoolean>(24)?.value(&mut cx);
+    let parent = cx.argument::<JsValue>(25)?;
+    let modal = cx.argument::<JsBoolean>(26)?.value(&mut cx);
+    let label = cx.argument::<JsValue>(27)?;
+    let theme = cx.argument::<JsString>(28)?.value(&mut cx);
+    let theme = match ThemePreference::parse(&theme) {
+        Some(theme) => theme,
+        None => return cx.throw_error(format!("unknown theme {}", theme)),
+    };
+    let cb = cx.argument::<JsFunction>(29)?.root(&mut cx);
+
+    let partition = partition
+        .downcast::<JsString, _>(&mut cx)
+        .ok()
+        .map(|partition| partition.value(&mut cx));
+    let mut scripts = Vec::new();
+    for script in initialization_scripts.to_vec(&mut cx)? {
+        let script = script.downcast_or_throw::<JsObject, _>(&mut cx)?;
+        let source = script
+            .get::<JsString, _, _>(&mut cx, "source")?
+            .value(&mut cx);
+        let all_frames = script
+            .get::<JsBoolean, _, _>(&mut cx, "allFrames")?
+            .value(&mut cx);
+        scripts.push(InitScript { source, all_frames });
+    }
+    let ipc_origins = match ipc_origins.downcast::<JsArray, _>(&mut cx) {
+        Ok(ipc_origins) => {
+            let origins = string_array(&mut cx, ipc_origins)?;
+            Some(
+                origins
+                    .iter()
+                    .map(|origin| security::origin(origin))
+                    .collect(),
+            )
+        }
+        Err(_) => None,
+    };
+    let csp = csp
+        .downcast::<JsString, _>(&mut cx)
+        .ok()
+        .map(|csp| csp.value(&mut cx));
+    let assets_dir = assets_dir
+        .downcast::<JsString, _>(&mut cx)
+        .ok()
+        .map(|assets_dir| PathBuf::from(assets_dir.value(&mut cx)));
+    let allowed_channels = match allowed_channels.downcast::<JsArray, _>(&mut cx) {
+        Ok(allowed_channels) => Some(string_array(&mut cx, allowed_channels)?),
+        Err(_) => None,
+    };
+    let denied_channels = match denied_channels.downcast::<JsArray, _>(&mut cx) {
+        Ok(denied_channels) => string_array(&mut cx, denied_channels)?,
+        Err(_) => Vec::new(),
+    };
+    let rate_limit = rate_limit
+        .downcast::<JsNumber, _>(&mut cx)
+        .ok()
+        .map(|rate_limit| rate_limit.value(&mut cx) as u32);
+    let parent = parent
+        .downcast::<JsBox<WindowIdBoxed>, _>(&mut cx)
+        .ok()
+        .map(|parent| parent.window_id.clone());
+    let label = label
+        .downcast::<JsString, _>(&mut cx)
+        .ok()
+        .map(|label| label.value(&mut cx));
+
+    let option = Options {
+        title,
         width,
         height,
         visible,
@@ -413,7 +1808,27 @@ fn create_new_window_js(mut cx: FunctionContext) -> JsResult<JsUndefined> {
         frameless,
         resizable,
         transparent,
-        initialization_script,
+        initialization_scripts: scripts,
+        file_drop,
+        file_drop_prevent_default,
+        downloads,
+        partition: Partition::new(partition, ephemeral),
+        security: SecurityPolicy {
+            ipc_origins,
+            remote_ipc,
+            file_ipc,
+            csp,
+            allowed_channels,
+            denied_channels,
+            rate_limit,
+        },
+        assets_dir,
+        zoom,
+        zoom_hotkeys,
+        parent,
+        modal,
+        label,
+        theme,
     };
     let proxy = proxy.deref();
     let proxy = proxy.proxy.clone();
@@ -509,6 +1924,127 @@ fn evaluate_script(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     Ok(cx.undefined())
 }
 
+fn add_child_webview(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let window_id = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
+    let url = cx.argument::<JsString>(2)?.value(&mut cx);
+    let bounds = cx.argument::<JsObject>(3)?;
+    let bounds = bounds_from_js(&mut cx, bounds)?;
+    let auto_resize_width = cx.argument::<JsBoolean>(4)?.value(&mut cx);
+    let auto_resize_height = cx.argument::<JsBoolean>(5)?.value(&mut cx);
+    let cb = cx.argument::<JsFunction>(6)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+    let window_id = window_id.deref();
+    let window_id = window_id.window_id.clone();
+
+    let options = ChildOptions {
+        url,
+        bounds,
+        auto_resize_width,
+        auto_resize_height,
+    };
+    let _ = proxy.send_event(UserEvents::AddChildWebview(window_id, options, cb));
+    Ok(cx.undefined())
+}
+
+fn set_child_webview_bounds(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let window_id = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
+    let id = cx.argument::<JsNumber>(2)?.value(&mut cx) as u64;
+    let bounds = cx.argument::<JsObject>(3)?;
+    let bounds = bounds_from_js(&mut cx, bounds)?;
+    let cb = cx.argument::<JsFunction>(4)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+    let window_id = window_id.deref();
+    let window_id = window_id.window_id.clone();
+
+    let _ = proxy.send_event(UserEvents::SetChildWebviewBounds(window_id, id, bounds, cb));
+    Ok(cx.undefined())
+}
+
+fn remove_child_webview(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let window_id = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
+    let id = cx.argument::<JsNumber>(2)?.value(&mut cx) as u64;
+    let cb = cx.argument::<JsFunction>(3)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+    let window_id = window_id.deref();
+    let window_id = window_id.window_id.clone();
+
+    let _ = proxy.send_event(UserEvents::RemoveChildWebview(window_id, id, cb));
+    Ok(cx.undefined())
+}
+
+fn evaluate_child_script(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let window_id = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
+    let id = cx.argument::<JsNumber>(2)?.value(&mut cx) as u64;
+    let script = cx.argument::<JsString>(3)?.value(&mut cx);
+    let cb = cx.argument::<JsFunction>(4)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+    let window_id = window_id.deref();
+    let window_id = window_id.window_id.clone();
+
+    let _ = proxy.send_event(UserEvents::EvaluateChildScript(window_id, id, script, cb));
+    Ok(cx.undefined())
+}
+
+fn bounds_from_js<'a, C: Context<'a>>(
+    cx: &mut C,
+    object: Handle<'a, JsObject>,
+) -> NeonResult<Bounds> {
+    let x = object.get::<JsNumber, _, _>(cx, "x")?.value(cx);
+    let y = object.get::<JsNumber, _, _>(cx, "y")?.value(cx);
+    let width = object.get::<JsNumber, _, _>(cx, "width")?.value(cx);
+    let height = object.get::<JsNumber, _, _>(cx, "height")?.value(cx);
+    Ok(Bounds {
+        x: x as i32,
+        y: y as i32,
+        width: width as i32,
+        height: height as i32,
+    })
+}
+
+fn add_initialization_script(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let window_id = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
+    let source = cx.argument::<JsString>(2)?.value(&mut cx);
+    let all_frames = cx.argument::<JsBoolean>(3)?.value(&mut cx);
+    let cb = cx.argument::<JsFunction>(4)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+    let window_id = window_id.deref();
+    let window_id = window_id.window_id.clone();
+
+    let script = InitScript { source, all_frames };
+    let _ = proxy.send_event(UserEvents::AddInitScript(window_id, script, cb));
+    Ok(cx.undefined())
+}
+
+fn remove_initialization_script(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let window_id = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
+    let id = cx.argument::<JsNumber>(2)?.value(&mut cx) as u64;
+    let cb = cx.argument::<JsFunction>(3)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+    let window_id = window_id.deref();
+    let window_id = window_id.window_id.clone();
+
+    let _ = proxy.send_event(UserEvents::RemoveInitScript(window_id, id, cb));
+    Ok(cx.undefined())
+}
+
 fn set_window_size(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
     let window_id = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
@@ -626,10 +2162,10 @@ fn close_devtools(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     Ok(cx.undefined())
 }
 
-fn set_frameless_window(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+fn set_zoom(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
     let window_id = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
-    let flag = cx.argument::<JsBoolean>(2)?.value(&mut cx);
+    let factor = cx.argument::<JsNumber>(2)?.value(&mut cx);
     let cb = cx.argument::<JsFunction>(3)?.root(&mut cx);
 
     let proxy = proxy.deref();
@@ -637,16 +2173,291 @@ fn set_frameless_window(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let window_id = window_id.deref();
     let window_id = window_id.window_id.clone();
 
-    let _ = proxy.send_event(UserEvents::SetFramelessWindow(window_id, flag, cb));
+    let _ = proxy.send_event(UserEvents::SetZoom(window_id, factor, cb));
     Ok(cx.undefined())
 }
 
-fn set_window_icon(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+fn get_zoom(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
     let window_id = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
-    let rgba = cx.argument::<JsBuffer>(2)?.as_slice(&cx).to_vec();
-    let width = cx.argument::<JsNumber>(3)?.value(&mut cx) as u32;
-    let height = cx.argument::<JsNumber>(4)?.value(&mut cx) as u32;
+    let cb = cx.argument::<JsFunction>(2)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+    let window_id = window_id.deref();
+    let window_id = window_id.window_id.clone();
+
+    let _ = proxy.send_event(UserEvents::GetZoom(window_id, cb));
+    Ok(cx.undefined())
+}
+
+fn get_theme(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let window_id = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
+    let cb = cx.argument::<JsFunction>(2)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+    let window_id = window_id.deref();
+    let window_id = window_id.window_id.clone();
+
+    let _ = proxy.send_event(UserEvents::GetTheme(window_id, cb));
+    Ok(cx.undefined())
+}
+
+fn capture_page(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let window_id = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
+    let rect = cx.argument::<JsValue>(2)?;
+    let rect = match rect.downcast::<JsObject, _>(&mut cx) {
+        Ok(rect) => {
+            let x = rect.get::<JsNumber, _, _>(&mut cx, "x")?.value(&mut cx);
+            let y = rect.get::<JsNumber, _, _>(&mut cx, "y")?.value(&mut cx);
+            let width = rect.get::<JsNumber, _, _>(&mut cx, "width")?.value(&mut cx);
+            let height = rect
+                .get::<JsNumber, _, _>(&mut cx, "height")?
+                .value(&mut cx);
+            Some(CaptureRect {
+                x: x as i32,
+                y: y as i32,
+                width: width as i32,
+                height: height as i32,
+            })
+        }
+        Err(_) => None,
+    };
+    let format = cx.argument::<JsString>(3)?.value(&mut cx);
+    let format = match CaptureFormat::parse(&format) {
+        Some(format) => format,
+        None => return cx.throw_error(format!("unknown image format {}", format)),
+    };
+    let cb = cx.argument::<JsFunction>(4)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+    let window_id = window_id.deref();
+    let window_id = window_id.window_id.clone();
+
+    let _ = proxy.send_event(UserEvents::CapturePage(window_id, rect, format, cb));
+    Ok(cx.undefined())
+}
+
+fn print_window(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let window_id = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
+    let silent = cx.argument::<JsBoolean>(2)?.value(&mut cx);
+    let printer = cx
+        .argument::<JsValue>(3)?
+        .downcast::<JsString, _>(&mut cx)
+        .ok()
+        .map(|printer| printer.value(&mut cx));
+    let cb = cx.argument::<JsFunction>(4)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+    let window_id = window_id.deref();
+    let window_id = window_id.window_id.clone();
+
+    let options = PrintOptions { silent, printer };
+    let _ = proxy.send_event(UserEvents::Print(window_id, options, cb));
+    Ok(cx.undefined())
+}
+
+fn print_to_pdf(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let window_id = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
+    // a paper name or `{ width, height }` in millimeters
+    let page_size = cx.argument::<JsValue>(2)?;
+    let page_size = match page_size.downcast::<JsString, _>(&mut cx) {
+        Ok(name) => PageSize::Named(name.value(&mut cx)),
+        Err(_) => {
+            let size = page_size.downcast_or_throw::<JsObject, _>(&mut cx)?;
+            let width = size.get::<JsNumber, _, _>(&mut cx, "width")?.value(&mut cx);
+            let height = size
+                .get::<JsNumber, _, _>(&mut cx, "height")?
+                .value(&mut cx);
+            PageSize::Custom(width, height)
+        }
+    };
+    let margins = cx.argument::<JsValue>(3)?;
+    let margins = match margins.downcast::<JsObject, _>(&mut cx) {
+        Ok(margins) => {
+            let top = margins
+                .get::<JsNumber, _, _>(&mut cx, "top")?
+                .value(&mut cx);
+            let right = margins
+                .get::<JsNumber, _, _>(&mut cx, "right")?
+                .value(&mut cx);
+            let bottom = margins
+                .get::<JsNumber, _, _>(&mut cx, "bottom")?
+                .value(&mut cx);
+            let left = margins
+                .get::<JsNumber, _, _>(&mut cx, "left")?
+                .value(&mut cx);
+            Some(Margins {
+                top,
+                right,
+                bottom,
+                left,
+            })
+        }
+        Err(_) => None,
+    };
+    let landscape = cx.argument::<JsBoolean>(4)?.value(&mut cx);
+    let cb = cx.argument::<JsFunction>(5)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+    let window_id = window_id.deref();
+    let window_id = window_id.window_id.clone();
+
+    let options = PdfOptions {
+        page_size,
+        margins,
+        landscape,
+    };
+    let _ = proxy.send_event(UserEvents::PrintToPdf(window_id, options, cb));
+    Ok(cx.undefined())
+}
+
+fn start_automation(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let port = cx.argument::<JsNumber>(1)?.value(&mut cx) as u16;
+    let token = cx.argument::<JsString>(2)?.value(&mut cx);
+    let cb = cx.argument::<JsFunction>(3)?;
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.deref().clone();
+
+    let this = cx.undefined();
+    let args = match automation::start(proxy, port, token) {
+        Ok(port) => vec![cx.null().upcast::<JsValue>(), cx.number(port).upcast()],
+        Err(message) => vec![cx.string(message).upcast()],
+    };
+    cb.call(&mut cx, this, args)?;
+    Ok(cx.undefined())
+}
+
+fn request_single_instance_lock(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let app_id = cx.argument::<JsString>(1)?.value(&mut cx);
+    let cb = cx.argument::<JsFunction>(2)?;
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.deref().clone();
+
+    let this = cx.undefined();
+    let args = match single_instance::request_lock(proxy, &app_id) {
+        Ok(locked) => vec![cx.null().upcast::<JsValue>(), cx.boolean(locked).upcast()],
+        Err(message) => vec![cx.string(message).upcast()],
+    };
+    cb.call(&mut cx, this, args)?;
+    Ok(cx.undefined())
+}
+
+fn register_url_scheme(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let app_id = cx.argument::<JsString>(0)?.value(&mut cx);
+    let scheme = cx.argument::<JsString>(1)?.value(&mut cx);
+    let name = cx.argument::<JsString>(2)?.value(&mut cx);
+    let command = cx.argument::<JsArray>(3)?;
+    let cb = cx.argument::<JsFunction>(4)?;
+
+    let command = string_array(&mut cx, command)?;
+    let registration = Registration {
+        app_id,
+        scheme,
+        name,
+        command,
+    };
+    let this = cx.undefined();
+    let error = match deep_link::register(&registration) {
+        Ok(()) => cx.null().upcast::<JsValue>(),
+        Err(message) => cx.string(message).upcast(),
+    };
+    cb.call(&mut cx, this, &[error])?;
+    Ok(cx.undefined())
+}
+
+fn unregister_url_scheme(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let app_id = cx.argument::<JsString>(0)?.value(&mut cx);
+    let scheme = cx.argument::<JsString>(1)?.value(&mut cx);
+    let cb = cx.argument::<JsFunction>(2)?;
+
+    let this = cx.undefined();
+    let args = match deep_link::unregister(&app_id, &scheme) {
+        Ok(removed) => vec![cx.null().upcast::<JsValue>(), cx.boolean(removed).upcast()],
+        Err(message) => vec![cx.string(message).upcast()],
+    };
+    cb.call(&mut cx, this, args)?;
+    Ok(cx.undefined())
+}
+
+fn is_url_scheme_registered(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let app_id = cx.argument::<JsString>(0)?.value(&mut cx);
+    let scheme = cx.argument::<JsString>(1)?.value(&mut cx);
+    let cb = cx.argument::<JsFunction>(2)?;
+
+    let this = cx.undefined();
+    let args = match deep_link::is_registered(&app_id, &scheme) {
+        Ok(registered) => vec![
+            cx.null().upcast::<JsValue>(),
+            cx.boolean(registered).upcast(),
+        ],
+        Err(message) => vec![cx.string(message).upcast()],
+    };
+    cb.call(&mut cx, this, args)?;
+    Ok(cx.undefined())
+}
+
+fn toggle_devtools(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let window_id = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
+    let cb = cx.argument::<JsFunction>(2)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+    let window_id = window_id.deref();
+    let window_id = window_id.window_id.clone();
+
+    let _ = proxy.send_event(UserEvents::ToggleDevtools(window_id, cb));
+    Ok(cx.undefined())
+}
+
+fn is_devtools_open(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let window_id = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
+    let cb = cx.argument::<JsFunction>(2)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+    let window_id = window_id.deref();
+    let window_id = window_id.window_id.clone();
+
+    let _ = proxy.send_event(UserEvents::IsDevtoolsOpen(window_id, cb));
+    Ok(cx.undefined())
+}
+
+fn set_frameless_window(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let window_id = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
+    let flag = cx.argument::<JsBoolean>(2)?.value(&mut cx);
+    let cb = cx.argument::<JsFunction>(3)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+    let window_id = window_id.deref();
+    let window_id = window_id.window_id.clone();
+
+    let _ = proxy.send_event(UserEvents::SetFramelessWindow(window_id, flag, cb));
+    Ok(cx.undefined())
+}
+
+fn set_window_icon(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let window_id = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
+    let rgba = cx.argument::<JsBuffer>(2)?.as_slice(&cx).to_vec();
+    let width = cx.argument::<JsNumber>(3)?.value(&mut cx) as u32;
+    let height = cx.argument::<JsNumber>(4)?.value(&mut cx) as u32;
     let cb = cx.argument::<JsFunction>(5)?.root(&mut cx);
 
     let proxy = proxy.deref();
@@ -660,6 +2471,299 @@ fn set_window_icon(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     Ok(cx.undefined())
 }
 
+fn show_message_box(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let kind = cx.argument::<JsString>(1)?.value(&mut cx);
+    let title = cx.argument::<JsString>(2)?.value(&mut cx);
+    let message = cx.argument::<JsString>(3)?.value(&mut cx);
+    let js_buttons = cx.argument::<JsArray>(4)?.to_vec(&mut cx)?;
+    let default_id = cx.argument::<JsNumber>(5)?.value(&mut cx) as usize;
+    let cancel_id = cx.argument::<JsNumber>(6)?.value(&mut cx) as usize;
+    let parent = cx.argument::<JsValue>(7)?;
+    let cb = cx.argument::<JsFunction>(8)?.root(&mut cx);
+
+    let mut buttons = Vec::new();
+    for button in js_buttons {
+        let button = button.downcast_or_throw::<JsString, _>(&mut cx)?;
+        buttons.push(button.value(&mut cx));
+    }
+    let parent_id = parent
+        .downcast::<JsBox<WindowIdBoxed>, _>(&mut cx)
+        .ok()
+        .map(|parent| parent.window_id.clone());
+
+    let options = MessageBoxOptions {
+        kind: MessageBoxType::from_str(&kind),
+        title,
+        message,
+        buttons,
+        default_id,
+        cancel_id,
+    };
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+
+    let _ = proxy.send_event(UserEvents::ShowMessageBox(options, parent_id, cb));
+    Ok(cx.undefined())
+}
+
+fn clipboard_read_text(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let cb = cx.argument::<JsFunction>(1)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+
+    let _ = proxy.send_event(UserEvents::ClipboardReadText(cb));
+    Ok(cx.undefined())
+}
+
+fn clipboard_write_text(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let text = cx.argument::<JsString>(1)?.value(&mut cx);
+    let cb = cx.argument::<JsFunction>(2)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+
+    let _ = proxy.send_event(UserEvents::ClipboardWriteText(text, cb));
+    Ok(cx.undefined())
+}
+
+fn clipboard_write_html(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let html = cx.argument::<JsString>(1)?.value(&mut cx);
+    let alt_text = cx.argument::<JsValue>(2)?;
+    let cb = cx.argument::<JsFunction>(3)?.root(&mut cx);
+
+    let alt_text = alt_text
+        .downcast::<JsString, _>(&mut cx)
+        .ok()
+        .map(|alt_text| alt_text.value(&mut cx));
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+
+    let _ = proxy.send_event(UserEvents::ClipboardWriteHtml(html, alt_text, cb));
+    Ok(cx.undefined())
+}
+
+fn clipboard_read_image(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let cb = cx.argument::<JsFunction>(1)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+
+    let _ = proxy.send_event(UserEvents::ClipboardReadImage(cb));
+    Ok(cx.undefined())
+}
+
+fn clipboard_write_image(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let rgba = cx.argument::<JsBuffer>(1)?.as_slice(&cx).to_vec();
+    let width = cx.argument::<JsNumber>(2)?.value(&mut cx) as u32;
+    let height = cx.argument::<JsNumber>(3)?.value(&mut cx) as u32;
+    let cb = cx.argument::<JsFunction>(4)?.root(&mut cx);
+
+    let image = ClipboardImage {
+        rgba,
+        width,
+        height,
+    };
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+
+    let _ = proxy.send_event(UserEvents::ClipboardWriteImage(image, cb));
+    Ok(cx.undefined())
+}
+
+fn clipboard_formats(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let cb = cx.argument::<JsFunction>(1)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+
+    let _ = proxy.send_event(UserEvents::ClipboardFormats(cb));
+    Ok(cx.undefined())
+}
+
+fn list_windows(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let cb = cx.argument::<JsFunction>(1)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+
+    let _ = proxy.send_event(UserEvents::ListWindows(cb));
+    Ok(cx.undefined())
+}
+
+fn get_window_by_label(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let label = cx.argument::<JsString>(1)?.value(&mut cx);
+    let cb = cx.argument::<JsFunction>(2)?.root(&mut cx);
+
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+
+    let _ = proxy.send_event(UserEvents::GetWindowByLabel(label, cb));
+    Ok(cx.undefined())
+}
+
+fn show_notification(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let title = cx.argument::<JsString>(1)?.value(&mut cx);
+    let body = cx.argument::<JsString>(2)?.value(&mut cx);
+    let icon = cx.argument::<JsValue>(3)?;
+    let js_actions = cx.argument::<JsArray>(4)?.to_vec(&mut cx)?;
+    let silent = cx.argument::<JsBoolean>(5)?.value(&mut cx);
+    let cb = cx.argument::<JsFunction>(6)?.root(&mut cx);
+
+    let icon = icon
+        .downcast::<JsString, _>(&mut cx)
+        .ok()
+        .map(|icon| icon.value(&mut cx));
+    let mut actions = Vec::new();
+    for action in js_actions {
+        let action = action.downcast_or_throw::<JsObject, _>(&mut cx)?;
+        let id = action.get::<JsString, _, _>(&mut cx, "id")?.value(&mut cx);
+        let title = action
+            .get::<JsString, _, _>(&mut cx, "title")?
+            .value(&mut cx);
+        actions.push((id, title));
+    }
+
+    let options = NotificationOptions {
+        title,
+        body,
+        icon,
+        actions,
+        silent,
+    };
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+
+    let _ = proxy.send_event(UserEvents::ShowNotification(options, cb));
+    Ok(cx.undefined())
+}
+
+fn resolve_download(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let id = cx.argument::<JsNumber>(1)?.value(&mut cx) as u64;
+    let path = cx.argument::<JsValue>(2)?;
+    let cb = cx.argument::<JsFunction>(3)?.root(&mut cx);
+
+    let path = path
+        .downcast::<JsString, _>(&mut cx)
+        .ok()
+        .map(|path| path.value(&mut cx));
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+
+    let _ = proxy.send_event(UserEvents::ResolveDownload(id, path, cb));
+    Ok(cx.undefined())
+}
+
+fn clear_data(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let partition = cx.argument::<JsValue>(1)?;
+    let cookies = cx.argument::<JsBoolean>(2)?.value(&mut cx);
+    let local_storage = cx.argument::<JsBoolean>(3)?.value(&mut cx);
+    let cache = cx.argument::<JsBoolean>(4)?.value(&mut cx);
+    let indexeddb = cx.argument::<JsBoolean>(5)?.value(&mut cx);
+    let cb = cx.argument::<JsFunction>(6)?.root(&mut cx);
+
+    let partition = partition
+        .downcast::<JsString, _>(&mut cx)
+        .ok()
+        .map(|partition| partition.value(&mut cx));
+    let kinds = [
+        (cookies, StorageKind::Cookies),
+        (local_storage, StorageKind::LocalStorage),
+        (cache, StorageKind::Cache),
+        (indexeddb, StorageKind::IndexedDb),
+    ]
+    .iter()
+    .filter(|(enabled, _)| *enabled)
+    .map(|(_, kind)| *kind)
+    .collect();
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+
+    let _ = proxy.send_event(UserEvents::ClearData(partition, kinds, cb));
+    Ok(cx.undefined())
+}
+
+fn get_data_usage(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let partition = cx.argument::<JsValue>(1)?;
+    let cb = cx.argument::<JsFunction>(2)?.root(&mut cx);
+
+    let partition = partition
+        .downcast::<JsString, _>(&mut cx)
+        .ok()
+        .map(|partition| partition.value(&mut cx));
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+
+    let _ = proxy.send_event(UserEvents::GetDataUsage(partition, cb));
+    Ok(cx.undefined())
+}
+
+fn get_cookies(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let partition = cx.argument::<JsValue>(1)?;
+    let url = cx.argument::<JsString>(2)?.value(&mut cx);
+    let cb = cx.argument::<JsFunction>(3)?.root(&mut cx);
+
+    let partition = partition
+        .downcast::<JsString, _>(&mut cx)
+        .ok()
+        .map(|partition| partition.value(&mut cx));
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+
+    let _ = proxy.send_event(UserEvents::GetCookies(partition, url, cb));
+    Ok(cx.undefined())
+}
+
+fn set_cookie(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let partition = cx.argument::<JsValue>(1)?;
+    let cookie = cx.argument::<JsObject>(2)?;
+    let cb = cx.argument::<JsFunction>(3)?.root(&mut cx);
+
+    let partition = partition
+        .downcast::<JsString, _>(&mut cx)
+        .ok()
+        .map(|partition| partition.value(&mut cx));
+    let cookie = cookie_from_js(&mut cx, cookie)?;
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+
+    let _ = proxy.send_event(UserEvents::SetCookie(partition, cookie, cb));
+    Ok(cx.undefined())
+}
+
+fn remove_cookie(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let proxy = cx.argument::<JsBox<IpcBoxed>>(0)?;
+    let partition = cx.argument::<JsValue>(1)?;
+    let name = cx.argument::<JsString>(2)?.value(&mut cx);
+    let url = cx.argument::<JsString>(3)?.value(&mut cx);
+    let cb = cx.argument::<JsFunction>(4)?.root(&mut cx);
+
+    let partition = partition
+        .downcast::<JsString, _>(&mut cx)
+        .ok()
+        .map(|partition| partition.value(&mut cx));
+    let proxy = proxy.deref();
+    let proxy = proxy.proxy.clone();
+
+    let _ = proxy.send_event(UserEvents::RemoveCookie(partition, name, url, cb));
+    Ok(cx.undefined())
+}
+
 fn compare_window_id(mut cx: FunctionContext) -> JsResult<JsBoolean> {
     let window_id_a = cx.argument::<JsBox<WindowIdBoxed>>(0)?;
     let window_id_b = cx.argument::<JsBox<WindowIdBoxed>>(1)?;
@@ -693,6 +2797,12 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("set_resizable_window", set_resizable_window)?;
     cx.export_function("set_visible_window", set_visible_window)?;
     cx.export_function("evaluate_script", evaluate_script)?;
+    cx.export_function("add_initialization_script", add_initialization_script)?;
+    cx.export_function("add_child_webview", add_child_webview)?;
+    cx.export_function("set_child_webview_bounds", set_child_webview_bounds)?;
+    cx.export_function("remove_child_webview", remove_child_webview)?;
+    cx.export_function("evaluate_child_script", evaluate_child_script)?;
+    cx.export_function("remove_initialization_script", remove_initialization_script)?;
     cx.export_function("set_window_size", set_window_size)?;
     cx.export_function("get_window_size", get_window_size)?;
     cx.export_function("set_minimized_window", set_minimized_window)?;
@@ -701,8 +2811,37 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("set_ignore_cursor_events", set_ignore_cursor_events)?;
     cx.export_function("open_devtools", open_devtools)?;
     cx.export_function("close_devtools", close_devtools)?;
+    cx.export_function("set_zoom", set_zoom)?;
+    cx.export_function("get_zoom", get_zoom)?;
+    cx.export_function("get_theme", get_theme)?;
+    cx.export_function("capture_page", capture_page)?;
+    cx.export_function("print", print_window)?;
+    cx.export_function("print_to_pdf", print_to_pdf)?;
+    cx.export_function("start_automation", start_automation)?;
+    cx.export_function("request_single_instance_lock", request_single_instance_lock)?;
+    cx.export_function("register_url_scheme", register_url_scheme)?;
+    cx.export_function("unregister_url_scheme", unregister_url_scheme)?;
+    cx.export_function("is_url_scheme_registered", is_url_scheme_registered)?;
+    cx.export_function("toggle_devtools", toggle_devtools)?;
+    cx.export_function("is_devtools_open", is_devtools_open)?;
     cx.export_function("set_frameless_window", set_frameless_window)?;
     cx.export_function("set_window_icon", set_window_icon)?;
+    cx.export_function("show_message_box", show_message_box)?;
+    cx.export_function("clipboard_read_text", clipboard_read_text)?;
+    cx.export_function("clipboard_write_text", clipboard_write_text)?;
+    cx.export_function("clipboard_write_html", clipboard_write_html)?;
+    cx.export_function("clipboard_read_image", clipboard_read_image)?;
+    cx.export_function("clipboard_write_image", clipboard_write_image)?;
+    cx.export_function("clipboard_formats", clipboard_formats)?;
+    cx.export_function("show_notification", show_notification)?;
+    cx.export_function("resolve_download", resolve_download)?;
+    cx.export_function("clear_data", clear_data)?;
+    cx.export_function("get_data_usage", get_data_usage)?;
+    cx.export_function("get_cookies", get_cookies)?;
+    cx.export_function("set_cookie", set_cookie)?;
+    cx.export_function("remove_cookie", remove_cookie)?;
+    cx.export_function("list_windows", list_windows)?;
+    cx.export_function("get_window_by_label", get_window_by_label)?;
     cx.export_function("compare_window_id", compare_window_id)?;
     cx.export_function("unsafe_quit", unsafe_quit)?;
     Ok(())
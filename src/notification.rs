@@ -0,0 +1,294 @@
+#[cfg(all(unix, not(target_os = "macos")))]
+use notify_rust::Hint;
+use notify_rust::Notification;
+
+pub struct NotificationOptions {
+    pub title: String,
+    pub body: String,
+    pub icon: Option<String>,
+    // (identifier, label)
+    pub actions: Vec<(String, String)>,
+    pub silent: bool,
+}
+
+pub enum NotificationEvent {
+    Click(u32),
+    Action(u32, String),
+    Close(u32),
+}
+
+// Shows the notification and returns its id. `on_event` is called from a
+// background thread once the user clicks, picks an action or dismisses it.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn show_notification<F>(options: NotificationOptions, on_event: F) -> Result<u32, String>
+where
+    F: FnOnce(NotificationEvent) + Send + 'static,
+{
+    let handle = build_notification(&options)
+        .show()
+        .map_err(|err| err.to_string())?;
+    let id = handle.id();
+    std::thread::spawn(move || {
+        handle.wait_for_action(|action| on_event(notification_event(id, action)));
+    });
+    Ok(id)
+}
+
+// `action` as notify-rust reports it
+#[cfg(all(unix, not(target_os = "macos")))]
+fn notification_event(id: u32, action: &str) -> NotificationEvent {
+    match action {
+        "default" => NotificationEvent::Click(id),
+        "__closed" => NotificationEvent::Close(id),
+        action => NotificationEvent::Action(id, action.to_string()),
+    }
+}
+
+// Other platforms don't report back what happened to a notification, ids are
+// only handed out so the Node side sees the same shape everywhere.
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+pub fn show_notification<F>(options: NotificationOptions, _on_event: F) -> Result<u32, String>
+where
+    F: FnOnce(NotificationEvent) + Send + 'static,
+{
+    use std::sync::atomic::{AtomicU32, Ordering};
+    static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+    build_notification(&options)
+        .show()
+        .map_err(|err| err.to_string())?;
+    Ok(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+fn build_notification(options: &NotificationOptions) -> Notification {
+    let mut notification = Notification::new();
+    notification.summary(&options.title).body(&options.body);
+    if let Some(icon) = &options.icon {
+        notification.icon(icon);
+    }
+    for (identifier, label) in &options.actions {
+        notification.action(identifier, label);
+    }
+    #[cfg(all(unix, not(target_os = "macos")))]
+    if options.silent {
+        notification.hint(Hint::SuppressSound(true));
+    }
+    notification
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> NotificationOptions {
+        NotificationOptions {
+            title: "Title".to_string(),
+            body: "Body".to_string(),
+            icon: None,
+            actions: vec![
+                ("reply".to_string(), "Reply".to_string()),
+                ("mark-read".to_string(), "Mark as read".to_string()),
+            ],
+            silent: false,
+        }
+    }
+
+    #[test]
+    fn actions_are_passed_on_in_order() {
+        let notification = build_notification(&options());
+        assert_eq!(notification.summary, "Title");
+        assert_eq!(notification.body, "Body");
+        assert_eq!(
+            notification.actions,
+            ["reply", "Reply", "mark-read", "Mark as read"]
+        );
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn silent_notifications_suppress_the_sound() {
+        let silent = NotificationOptions {
+            silent: true,
+            ..options()
+        };
+        let hint = Hint::SuppressSound(true);
+        assert!(build_notification(&silent).hints.contains(&hint));
+        assert!(!build_notification(&options()).hints.contains(&hint));
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn actions_are_reported_as_events() {
+        assert!(matches!(
+            notification_event(7, "default"),
+            NotificationEvent::Click(7)
+        ));
+        assert!(matches!(
+            notification_event(7, "__closed"),
+            NotificationEvent::Close(7)
+        ));
+        match notification_event(7, "reply") {
+            NotificationEvent::Action(7, action) => assert_eq!(action, "reply"),
+            _ => panic!("expected an action event"),
+        }
+    }
+
+    // stands in for the desktop's notification daemon on a private session bus
+    #[cfg(all(unix, not(target_os = "macos")))]
+    mod daemon {
+        use std::collections::HashMap;
+        use std::io::{BufRead, BufReader};
+        use std::process::{Child, Command, Stdio};
+        use std::sync::mpsc::Receiver;
+        use std::sync::{Arc, Mutex};
+        use std::time::Duration;
+        use zbus::blocking::{Connection, ConnectionBuilder};
+        use zbus::zvariant::{DynamicType, OwnedValue};
+
+        // (summary, actions) of every notification shown
+        type Shown = Arc<Mutex<Vec<(String, Vec<String>)>>>;
+
+        struct Notifications {
+            shown: Shown,
+        }
+
+        #[zbus::dbus_interface(name = "org.freedesktop.Notifications")]
+        impl Notifications {
+            #[allow(clippy::too_many_arguments)]
+            fn notify(
+                &self,
+                _app_name: String,
+                _replaces_id: u32,
+                _app_icon: String,
+                summary: String,
+                _body: String,
+                actions: Vec<String>,
+                _hints: HashMap<String, OwnedValue>,
+                _expire_timeout: i32,
+            ) -> u32 {
+                let mut shown = self.shown.lock().unwrap();
+                shown.push((summary, actions));
+                shown.len() as u32
+            }
+        }
+
+        pub struct Daemon {
+            bus: Child,
+            connection: Connection,
+            pub shown: Shown,
+        }
+
+        impl Daemon {
+            // notify-rust connects to whatever DBUS_SESSION_BUS_ADDRESS names, so
+            // only one daemon may run per test binary
+            pub fn start() -> Daemon {
+                let mut bus = Command::new("dbus-daemon")
+                    .args(["--session", "--nofork", "--print-address"])
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::null())
+                    .spawn()
+                    .expect("dbus-daemon is needed to run the notification tests");
+                let mut address = String::new();
+                BufReader::new(bus.stdout.take().unwrap())
+                    .read_line(&mut address)
+                    .unwrap();
+                let address = address.trim();
+                std::env::set_var("DBUS_SESSION_BUS_ADDRESS", address);
+                let shown = Shown::default();
+                let notifications = Notifications {
+                    shown: shown.clone(),
+                };
+                let connection = ConnectionBuilder::address(address)
+                    .unwrap()
+                    .name("org.freedesktop.Notifications")
+                    .unwrap()
+                    .serve_at("/org/freedesktop/Notifications", notifications)
+                    .unwrap()
+                    .build()
+                    .unwrap();
+                Daemon {
+                    bus,
+                    connection,
+                    shown,
+                }
+            }
+
+            // notifications are listened for on a thread that subscribes after
+            // `show_notification` returns, so keep signalling until it reacts
+            pub fn signal_until_received<B, T>(
+                &self,
+                signal: &str,
+                body: &B,
+                receiver: &Receiver<T>,
+            ) -> T
+            where
+                B: serde::Serialize + DynamicType,
+            {
+                for _ in 0..100 {
+                    self.connection
+                        .emit_signal(
+                            None::<&str>,
+                            "/org/freedesktop/Notifications",
+                            "org.freedesktop.Notifications",
+                            signal,
+                            body,
+                        )
+                        .unwrap();
+                    if let Ok(received) = receiver.recv_timeout(Duration::from_millis(50)) {
+                        return received;
+                    }
+                }
+                panic!("{} was never reported", signal);
+            }
+        }
+
+        impl Drop for Daemon {
+            fn drop(&mut self) {
+                let _ = self.bus.kill();
+                let _ = self.bus.wait();
+            }
+        }
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn daemon_clicks_actions_and_closes_are_reported() {
+        use std::sync::mpsc;
+
+        let daemon = daemon::Daemon::start();
+        let show = || {
+            let (sender, receiver) = mpsc::channel();
+            let id = show_notification(options(), move |event| {
+                let _ = sender.send(event);
+            })
+            .unwrap();
+            (id, receiver)
+        };
+
+        let (id, receiver) = show();
+        let event = daemon.signal_until_received("ActionInvoked", &(id, "default"), &receiver);
+        assert!(matches!(event, NotificationEvent::Click(clicked) if clicked == id));
+
+        let (id, receiver) = show();
+        let event = daemon.signal_until_received("ActionInvoked", &(id, "reply"), &receiver);
+        match event {
+            NotificationEvent::Action(acted, action) => {
+                assert_eq!(acted, id);
+                assert_eq!(action, "reply");
+            }
+            _ => panic!("expected an action event"),
+        }
+
+        let (id, receiver) = show();
+        // 2 is "dismissed by the user"
+        let event = daemon.signal_until_received("NotificationClosed", &(id, 2u32), &receiver);
+        assert!(matches!(event, NotificationEvent::Close(closed) if closed == id));
+
+        let shown = daemon.shown.lock().unwrap();
+        assert_eq!(shown.len(), 3);
+        for (summary, actions) in shown.iter() {
+            assert_eq!(summary, "Title");
+            assert_eq!(actions, &["reply", "Reply", "mark-read", "Mark as read"]);
+        }
+    }
+}
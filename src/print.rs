@@ -0,0 +1,187 @@
+pub use platform::{print, print_to_pdf};
+
+pub struct PrintOptions {
+    // print to the default printer without showing the dialog
+    pub silent: bool,
+    pub printer: Option<String>,
+}
+
+pub enum PageSize {
+    // a paper name like "a4" or "letter"
+    Named(String),
+    // width and height in millimeters
+    Custom(f64, f64),
+}
+
+// in millimeters
+#[derive(Clone, Copy)]
+pub struct Margins {
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+pub struct PdfOptions {
+    pub page_size: PageSize,
+    // the paper's default margins when `None`
+    pub margins: Option<Margins>,
+    pub landscape: bool,
+}
+
+// resolves with whether the page was printed, false when the dialog was cancelled
+pub type PrintCallback = Box<dyn FnOnce(Result<bool, String>) + Send>;
+pub type PdfCallback = Box<dyn FnOnce(Result<Vec<u8>, String>) + Send>;
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{Margins, PageSize, PdfCallback, PdfOptions, PrintCallback, PrintOptions};
+    use gtk::{PageOrientation, PageSetup, PaperSize, PrintSettings, Unit};
+    use std::cell::RefCell;
+    use std::path::Path;
+    use std::rc::Rc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use webkit2gtk::{PrintOperation, PrintOperationExt, PrintOperationResponse};
+    use wry::{
+        application::platform::unix::WindowExtUnix,
+        webview::{WebView, WebviewExtUnix},
+    };
+
+    // the printer of GTK's file backend, it needs no print server
+    const FILE_PRINTER: &str = "Print to File";
+
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+    pub fn print(webview: &WebView, options: PrintOptions, callback: PrintCallback) {
+        let operation = PrintOperation::new(&*webview.webview());
+        if let Some(printer) = &options.printer {
+            let settings = PrintSettings::new();
+            settings.set_printer(printer);
+            operation.set_print_settings(&settings);
+        }
+        if options.silent {
+            run(&operation, move |result| callback(result.map(|_| true)));
+            operation.print();
+            return;
+        }
+        // the dialog runs modal, the job itself is sent once it's closed
+        match operation.run_dialog(Some(webview.window().gtk_window())) {
+            PrintOperationResponse::Print => {
+                run(&operation, move |result| callback(result.map(|_| true)))
+            }
+            _ => callback(Ok(false)),
+        }
+    }
+
+    pub fn print_to_pdf(webview: &WebView, options: PdfOptions, callback: PdfCallback) {
+        let path = std::env::temp_dir().join(format!(
+            "webarea-{}-{}.pdf",
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        let page_setup = match page_setup(&options) {
+            Ok(page_setup) => page_setup,
+            Err(err) => return callback(Err(err)),
+        };
+        let settings = PrintSettings::new();
+        settings.set_printer(FILE_PRINTER);
+        settings.set("output-file-format", Some("pdf"));
+        settings.set("output-uri", Some(&file_uri(&path)));
+
+        let operation = PrintOperation::new(&*webview.webview());
+        operation.set_page_setup(&page_setup);
+        operation.set_print_settings(&settings);
+        run(&operation, move |result| {
+            let result = result.and_then(|_| std::fs::read(&path).map_err(|err| err.to_string()));
+            let _ = std::fs::remove_file(&path);
+            callback(result);
+        });
+        operation.print();
+    }
+
+    // Calls `done` once the operation finished. "failed" is always followed
+    // by "finished", the operation is kept alive until then.
+    fn run<F: FnOnce(Result<(), String>) + 'static>(operation: &PrintOperation, done: F) {
+        let error = Rc::new(RefCell::new(None));
+        let failed_error = error.clone();
+        operation.connect_failed(move |_, err| {
+            *failed_error.borrow_mut() = Some(err.to_string());
+        });
+        let pending = RefCell::new(Some((operation.clone(), done)));
+        operation.connect_finished(move |_| {
+            if let Some((_operation, done)) = pending.borrow_mut().take() {
+                done(error.borrow_mut().take().map_or(Ok(()), Err));
+            }
+        });
+    }
+
+    fn page_setup(options: &PdfOptions) -> Result<PageSetup, String> {
+        let paper_size = match &options.page_size {
+            PageSize::Named(name) => {
+                let name = match name.to_ascii_lowercase().as_str() {
+                    "a3" => "iso_a3",
+                    "a4" => "iso_a4",
+                    "a5" => "iso_a5",
+                    "letter" => "na_letter",
+                    "legal" => "na_legal",
+                    "tabloid" => "na_ledger",
+                    _ => return Err(format!("unknown page size {}", name)),
+                };
+                PaperSize::new(Some(name))
+            }
+            PageSize::Custom(width, height) => {
+                if !(*width > 0.0 && *height > 0.0) {
+                    return Err("page size must be positive".to_string());
+                }
+                PaperSize::new_custom("custom", "Custom", *width, *height, Unit::Mm)
+            }
+        };
+        let page_setup = PageSetup::new();
+        page_setup.set_paper_size_and_default_margins(&paper_size);
+        if options.landscape {
+            page_setup.set_orientation(PageOrientation::Landscape);
+        }
+        if let Some(Margins {
+            top,
+            right,
+            bottom,
+            left,
+        }) = options.margins
+        {
+            page_setup.set_top_margin(top, Unit::Mm);
+            page_setup.set_right_margin(right, Unit::Mm);
+            page_setup.set_bottom_margin(bottom, Unit::Mm);
+            page_setup.set_left_margin(left, Unit::Mm);
+        }
+        Ok(page_setup)
+    }
+
+    fn file_uri(path: &Path) -> String {
+        gtk::glib::filename_to_uri(path, None)
+            .map(|uri| uri.to_string())
+            .unwrap_or_else(|_| format!("file://{}", path.display()))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::{PdfCallback, PdfOptions, PrintCallback, PrintOptions};
+    use wry::webview::WebView;
+
+    // wry can only open the engine's print dialog here
+    pub fn print(webview: &WebView, options: PrintOptions, callback: PrintCallback) {
+        if options.silent || options.printer.is_some() {
+            return callback(Err(
+                "silent printing is not supported on this platform".to_string()
+            ));
+        }
+        // the dialog only reports back on Linux, so shown counts as printed
+        callback(webview.print().map(|_| true).map_err(|err| err.to_string()));
+    }
+
+    pub fn print_to_pdf(_webview: &WebView, _options: PdfOptions, callback: PdfCallback) {
+        callback(Err(
+            "printing to pdf is not supported on this platform".to_string()
+        ));
+    }
+}
@@ -0,0 +1,163 @@
+pub use platform::request_lock;
+
+#[cfg(unix)]
+mod platform {
+    use crate::UserEvents;
+    use serde_json::{json, Value};
+    use std::fs::{DirBuilder, File, OpenOptions};
+    use std::io::{ErrorKind, Read, Write};
+    use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt, PermissionsExt};
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::thread;
+    use std::time::Duration;
+    use wry::application::event_loop::EventLoopProxy;
+
+    // how long the second instance waits for the first to take its arguments
+    const FORWARD_TIMEOUT: Duration = Duration::from_secs(5);
+
+    static HELD: AtomicBool = AtomicBool::new(false);
+
+    // The first instance listens on a socket named after the app, later ones
+    // find it there, hand over their arguments and get false back. A socket
+    // file nobody listens on is left over from a crashed instance and taken over.
+    pub fn request_lock(proxy: EventLoopProxy<UserEvents>, app_id: &str) -> Result<bool, String> {
+        // asking twice must not forward to ourselves
+        if HELD.load(Ordering::SeqCst) {
+            return Ok(true);
+        }
+        let (path, lock_path) = paths(app_id)?;
+        // Instances starting together look at the socket one at a time, or
+        // one could remove the socket another has just bound.
+        let _lock = lock(&lock_path)?;
+        match UnixStream::connect(&path) {
+            Ok(stream) => return forward(stream).map(|_| false),
+            Err(err) if err.kind() == ErrorKind::ConnectionRefused => {
+                let _ = std::fs::remove_file(&path);
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => {}
+            Err(err) => return Err(format!("could not reach the running instance: {}", err)),
+        }
+        let listener = UnixListener::bind(&path)
+            .map_err(|err| format!("could not create the instance lock: {}", err))?;
+        HELD.store(true, Ordering::SeqCst);
+        listen(listener, proxy);
+        Ok(true)
+    }
+
+    // waits for other instances, the lock goes with the returned file
+    fn lock(path: &Path) -> Result<File, String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .mode(0o600)
+            .open(path)
+            .map_err(|err| format!("could not create the instance lock: {}", err))?;
+        loop {
+            if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } == 0 {
+                return Ok(file);
+            }
+            let err = std::io::Error::last_os_error();
+            if err.kind() != ErrorKind::Interrupted {
+                return Err(format!("could not create the instance lock: {}", err));
+            }
+        }
+    }
+
+    fn forward(mut stream: UnixStream) -> Result<(), String> {
+        let cwd = std::env::current_dir()
+            .map(|cwd| cwd.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let argv: Vec<String> = std::env::args_os()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        let message = json!({ "argv": argv, "cwd": cwd }).to_string();
+        let _ = stream.set_read_timeout(Some(FORWARD_TIMEOUT));
+        let _ = stream.set_write_timeout(Some(FORWARD_TIMEOUT));
+        stream
+            .write_all(message.as_bytes())
+            .and_then(|_| stream.shutdown(std::net::Shutdown::Write))
+            .map_err(|err| format!("could not reach the running instance: {}", err))?;
+        // the first instance closes the connection once it has the message
+        let _ = stream.read_to_end(&mut Vec::new());
+        Ok(())
+    }
+
+    fn listen(listener: UnixListener, proxy: EventLoopProxy<UserEvents>) {
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let _ = stream.set_read_timeout(Some(FORWARD_TIMEOUT));
+                let mut message = String::new();
+                if stream.read_to_string(&mut message).is_err() {
+                    continue;
+                }
+                if let Some((argv, cwd)) = parse(&message) {
+                    let _ = proxy.send_event(UserEvents::SecondInstance(argv, cwd));
+                }
+            }
+        });
+    }
+
+    fn parse(message: &str) -> Option<(Vec<String>, String)> {
+        let message: Value = serde_json::from_str(message).ok()?;
+        let argv = message["argv"]
+            .as_array()?
+            .iter()
+            .filter_map(|arg| arg.as_str().map(str::to_string))
+            .collect();
+        let cwd = message["cwd"].as_str()?.to_string();
+        Some((argv, cwd))
+    }
+
+    // The socket and its lock file are made inside a dir only the user can
+    // get into, so nobody else can reach them from the moment they exist. The
+    // runtime dir is that already, in the shared temp dir the user gets one
+    // of their own.
+    fn paths(app_id: &str) -> Result<(PathBuf, PathBuf), String> {
+        let name: String = app_id
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => c,
+                _ => '_',
+            })
+            .collect();
+        if name.is_empty() {
+            return Err("an app id is needed for the instance lock".to_string());
+        }
+        let dir = match dirs::runtime_dir() {
+            Some(dir) => dir,
+            None => private_temp_dir()?,
+        };
+        let socket = dir.join(format!("{}.instance", name));
+        Ok((socket, dir.join(format!("{}.lock", name))))
+    }
+
+    fn private_temp_dir() -> Result<PathBuf, String> {
+        let user = std::env::var("USER").unwrap_or_default();
+        let dir = std::env::temp_dir().join(format!("webarea-{}", user));
+        match DirBuilder::new().mode(0o700).create(&dir) {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => {}
+            Err(err) => return Err(format!("could not create the instance lock: {}", err)),
+        }
+        // someone else may have made it first
+        let metadata = std::fs::symlink_metadata(&dir).map_err(|err| err.to_string())?;
+        if !metadata.is_dir() || metadata.permissions().mode() & 0o077 != 0 {
+            return Err(format!("{} is not a private directory", dir.display()));
+        }
+        Ok(dir)
+    }
+}
+
+#[cfg(not(unix))]
+mod platform {
+    use crate::UserEvents;
+    use wry::application::event_loop::EventLoopProxy;
+
+    pub fn request_lock(_proxy: EventLoopProxy<UserEvents>, _app_id: &str) -> Result<bool, String> {
+        Err("the single instance lock is not supported on this platform".to_string())
+    }
+}
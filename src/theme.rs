@@ -0,0 +1,197 @@
+use crate::UserEvents;
+use std::collections::HashMap;
+use wry::application::{
+    event_loop::EventLoopProxy,
+    window::{Theme, Window, WindowBuilder, WindowId},
+};
+
+pub use platform::apply;
+
+#[derive(Clone, Copy)]
+pub enum ThemePreference {
+    Light,
+    Dark,
+    // follow the OS and its changes
+    System,
+}
+
+impl ThemePreference {
+    pub fn parse(theme: &str) -> Option<Self> {
+        match theme {
+            "light" => Some(ThemePreference::Light),
+            "dark" => Some(ThemePreference::Dark),
+            "system" => Some(ThemePreference::System),
+            _ => None,
+        }
+    }
+
+    // what the window builder takes, `None` follows the OS
+    fn window_theme(self) -> Option<Theme> {
+        match self {
+            ThemePreference::Light => Some(Theme::Light),
+            ThemePreference::Dark => Some(Theme::Dark),
+            ThemePreference::System => None,
+        }
+    }
+}
+
+pub fn theme_name(theme: Theme) -> &'static str {
+    if matches!(theme, Theme::Dark) {
+        "dark"
+    } else {
+        "light"
+    }
+}
+
+pub fn with_theme(builder: WindowBuilder, theme: ThemePreference) -> WindowBuilder {
+    platform::with_theme(builder, theme.window_theme())
+}
+
+// The theme every window was last reported with, changes are only passed on
+// once.
+pub struct Themes {
+    themes: HashMap<WindowId, Theme>,
+    watching: bool,
+}
+
+impl Themes {
+    pub fn new() -> Self {
+        Themes {
+            themes: HashMap::new(),
+            watching: false,
+        }
+    }
+
+    pub fn add(&mut self, window: &Window, proxy: &EventLoopProxy<UserEvents>) {
+        if !self.watching {
+            self.watching = platform::watch(proxy.clone());
+        }
+        self.themes.insert(window.id(), platform::theme(window));
+    }
+
+    pub fn current(&self, window_id: &WindowId) -> &'static str {
+        theme_name(self.themes.get(window_id).copied().unwrap_or_default())
+    }
+
+    // false when the window already had the theme, or is closed
+    pub fn changed(&mut self, window_id: &WindowId, theme: Theme) -> bool {
+        match self.themes.get_mut(window_id) {
+            Some(current) if *current != theme => {
+                *current = theme;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // the windows that didn't have the theme yet
+    pub fn changed_all(&mut self, theme: Theme) -> Vec<WindowId> {
+        let window_ids: Vec<WindowId> = self.themes.keys().copied().collect();
+        window_ids
+            .into_iter()
+            .filter(|window_id| self.changed(window_id, theme))
+            .collect()
+    }
+
+    pub fn release(&mut self, window_id: &WindowId) {
+        self.themes.remove(window_id);
+    }
+}
+
+// WebKitGTK answers `prefers-color-scheme` from GTK's dark theme preference,
+// which is shared by the whole process. The last window forcing a theme
+// sets it for every window following the system as well. tao doesn't report
+// theme changes here, they're read from GTK's settings instead.
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::ThemePreference;
+    use crate::UserEvents;
+    use gtk::prelude::*;
+    use gtk::traits::SettingsExt;
+    use wry::application::{
+        event_loop::EventLoopProxy,
+        platform::unix::WindowExtUnix,
+        window::{Theme, Window, WindowBuilder},
+    };
+
+    pub fn with_theme(builder: WindowBuilder, _theme: Option<Theme>) -> WindowBuilder {
+        builder
+    }
+
+    pub fn apply(window: &Window, theme: ThemePreference) {
+        let dark = match theme {
+            ThemePreference::Light => false,
+            ThemePreference::Dark => true,
+            ThemePreference::System => return,
+        };
+        if let Some(settings) = window.gtk_window().settings() {
+            settings.set_gtk_application_prefer_dark_theme(dark);
+        }
+    }
+
+    pub fn theme(_window: &Window) -> Theme {
+        gtk::Settings::default()
+            .map(|settings| settings_theme(&settings))
+            .unwrap_or_default()
+    }
+
+    pub fn watch(proxy: EventLoopProxy<UserEvents>) -> bool {
+        let settings = match gtk::Settings::default() {
+            Some(settings) => settings,
+            None => return false,
+        };
+        for property in &["gtk-application-prefer-dark-theme", "gtk-theme-name"] {
+            let proxy = proxy.clone();
+            settings.connect_notify_local(Some(property), move |settings, _| {
+                let _ = proxy.send_event(UserEvents::ThemeChanged(settings_theme(settings)));
+            });
+        }
+        true
+    }
+
+    // dark variants of a theme are named after it, like "Adwaita-dark"
+    fn settings_theme(settings: &gtk::Settings) -> Theme {
+        let name = settings
+            .gtk_theme_name()
+            .map(|name| name.to_ascii_lowercase())
+            .unwrap_or_default();
+        if settings.is_gtk_application_prefer_dark_theme()
+            || name.ends_with("-dark")
+            || name.ends_with(":dark")
+        {
+            Theme::Dark
+        } else {
+            Theme::Light
+        }
+    }
+}
+
+// The webview takes the window's appearance on macOS. WebView2 follows the
+// OS theme whatever the window uses. tao reports changes as window events.
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::ThemePreference;
+    use crate::UserEvents;
+    #[cfg(target_os = "macos")]
+    use wry::application::platform::macos::{WindowBuilderExtMacOS, WindowExtMacOS};
+    #[cfg(target_os = "windows")]
+    use wry::application::platform::windows::{WindowBuilderExtWindows, WindowExtWindows};
+    use wry::application::{
+        event_loop::EventLoopProxy,
+        window::{Theme, Window, WindowBuilder},
+    };
+
+    pub fn with_theme(builder: WindowBuilder, theme: Option<Theme>) -> WindowBuilder {
+        builder.with_theme(theme)
+    }
+
+    pub fn apply(_window: &Window, _theme: ThemePreference) {}
+
+    pub fn theme(window: &Window) -> Theme {
+        window.theme()
+    }
+
+    pub fn watch(_proxy: EventLoopProxy<UserEvents>) -> bool {
+        true
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use wry::{application::window::WindowId, webview::WebContext};
+
+const DEFAULT_PARTITION: &str = "";
+
+static NEXT_EPHEMERAL_ID: AtomicUsize = AtomicUsize::new(0);
+
+pub enum Partition {
+    Default,
+    Named(String),
+    Ephemeral,
+}
+
+impl Partition {
+    pub fn new(name: Option<String>, ephemeral: bool) -> Self {
+        match name {
+            _ if ephemeral => Partition::Ephemeral,
+            Some(name) => Partition::Named(name),
+            None => Partition::Default,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum StorageKind {
+    Cookies,
+    LocalStorage,
+    Cache,
+    IndexedDb,
+}
+
+impl StorageKind {
+    // Where the engine keeps each kind of data inside a context's directory.
+    #[cfg(target_os = "linux")]
+    fn paths(self, directory: &Path) -> Vec<PathBuf> {
+        match self {
+            StorageKind::Cookies => vec![directory.join("cookies")],
+            StorageKind::LocalStorage => vec![directory.join("localstorage")],
+            StorageKind::IndexedDb => vec![directory.join("databases").join("indexeddb")],
+            // kept outside of the data directory, see `platform`
+            StorageKind::Cache => Vec::new(),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn paths(self, directory: &Path) -> Vec<PathBuf> {
+        let profile = directory.join("EBWebView").join("Default");
+        match self {
+            StorageKind::Cookies => vec![
+                profile.join("Cookies"),
+                profile.join("Cookies-journal"),
+                profile.join("Network").join("Cookies"),
+                profile.join("Network").join("Cookies-journal"),
+            ],
+            StorageKind::LocalStorage => vec![profile.join("Local Storage")],
+            StorageKind::IndexedDb => vec![profile.join("IndexedDB")],
+            StorageKind::Cache => vec![profile.join("Cache"), profile.join("Code Cache")],
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct DataUsage {
+    pub cookies: u64,
+    pub local_storage: u64,
+    pub cache: u64,
+    pub indexeddb: u64,
+}
+
+impl DataUsage {
+    pub fn total(&self) -> u64 {
+        self.cookies + self.local_storage + self.cache + self.indexeddb
+    }
+}
+
+pub type ClearDataCallback = Box<dyn FnOnce(Result<(), String>) + Send>;
+pub type DataUsageCallback = Box<dyn FnOnce(Result<DataUsage, String>) + Send>;
+type CacheUsageCallback = Box<dyn FnOnce(Result<u64, String>) + Send>;
+
+// The context of one window. The webview engines need a directory for every
+// context, so "in-memory" contexts get a throwaway one which is removed with
+// the context.
+struct WindowContext {
+    context: WebContext,
+    // `None` for ephemeral contexts
+    partition: Option<String>,
+    directory: PathBuf,
+}
+
+impl Drop for WindowContext {
+    fn drop(&mut self) {
+        if self.partition.is_none() {
+            let _ = std::fs::remove_dir_all(&self.directory);
+        }
+    }
+}
+
+// Keeps the WebContext of every window alive for as long as the window is
+// open. Windows don't share contexts: on Linux a context's content manager
+// collects the scripts and IPC handlers of all its webviews and wry never
+// removes them, so windows sharing one would run each other's scripts and
+// receive each other's messages. Windows of a partition share its directory
+// instead.
+pub struct WebContexts {
+    data_dir: PathBuf,
+    windows: HashMap<WindowId, WindowContext>,
+}
+
+impl WebContexts {
+    pub fn new(data_dir: PathBuf) -> Self {
+        WebContexts {
+            data_dir,
+            windows: HashMap::new(),
+        }
+    }
+
+    fn partition_dir(&self, name: &str) -> Result<PathBuf, String> {
+        if name == DEFAULT_PARTITION {
+            return Ok(self.data_dir.clone());
+        }
+        let valid = name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.');
+        if !valid || name.starts_with('.') {
+            return Err(format!("invalid partition name \"{}\"", name));
+        }
+        Ok(self.data_dir.join("partitions").join(name))
+    }
+
+    pub fn get(
+        &mut self,
+        window_id: WindowId,
+        partition: &Partition,
+    ) -> Result<&mut WebContext, String> {
+        let name = match partition {
+            Partition::Default => DEFAULT_PARTITION,
+            Partition::Named(name) => name.as_str(),
+            Partition::Ephemeral => {
+                let id = NEXT_EPHEMERAL_ID.fetch_add(1, Ordering::Relaxed);
+                let directory = std::env::temp_dir().join(format!(
+                    "webarea-ephemeral-{}-{}",
+                    std::process::id(),
+                    id
+                ));
+                return Ok(self.insert(window_id, None, directory));
+            }
+        };
+        let directory = self.partition_dir(name)?;
+        std::fs::create_dir_all(&directory).map_err(|err| err.to_string())?;
+        Ok(self.insert(window_id, Some(name.to_string()), directory))
+    }
+
+    fn insert(
+        &mut self,
+        window_id: WindowId,
+        partition: Option<String>,
+        directory: PathBuf,
+    ) -> &mut WebContext {
+        let window = WindowContext {
+            context: WebContext::new(Some(directory.clone())),
+            partition,
+            directory,
+        };
+        self.windows.insert(window_id, window);
+        &mut self.windows.get_mut(&window_id).unwrap().context
+    }
+
+    // the open windows of the partition, `None` being the default partition
+    pub fn partition_windows(&self, partition: &Option<String>) -> Vec<WindowId> {
+        let name = partition.as_deref().unwrap_or(DEFAULT_PARTITION);
+        self.windows
+            .iter()
+            .filter(|(_, window)| window.partition.as_deref() == Some(name))
+            .map(|(window_id, _)| *window_id)
+            .collect()
+    }
+
+    pub fn release(&mut self, window_id: &WindowId) {
+        self.windows.remove(window_id);
+    }
+
+    pub fn clear_data(
+        &mut self,
+        partition: Option<String>,
+        kinds: &[StorageKind],
+        callback: ClearDataCallback,
+    ) {
+        let name = partition.unwrap_or_default();
+        let directory = match self.partition_dir(&name) {
+            Ok(directory) => directory,
+            Err(err) => return callback(Err(err)),
+        };
+        // the engine keeps the files of open contexts in use
+        if self
+            .windows
+            .values()
+            .any(|window| window.partition.as_deref() == Some(name.as_str()))
+        {
+            return callback(Err(format!(
+                "partition \"{}\" is still used by open windows",
+                name
+            )));
+        }
+        for kind in kinds {
+            for path in kind.paths(&directory) {
+                if let Err(err) = remove_path(&path) {
+                    return callback(Err(format!("{}: {}", path.display(), err)));
+                }
+            }
+        }
+        if kinds.iter().any(|kind| matches!(kind, StorageKind::Cache)) {
+            platform::clear_cache(callback);
+        } else {
+            callback(Ok(()));
+        }
+    }
+
+    pub fn data_usage(&self, partition: Option<String>, callback: DataUsageCallback) {
+        let directory = match self.partition_dir(&partition.unwrap_or_default()) {
+            Ok(directory) => directory,
+            Err(err) => return callback(Err(err)),
+        };
+        let usage = |kind: StorageKind| {
+            kind.paths(&directory)
+                .iter()
+                .map(|path| path_size(path))
+                .sum()
+        };
+        let usage = DataUsage {
+            cookies: usage(StorageKind::Cookies),
+            local_storage: usage(StorageKind::LocalStorage),
+            cache: usage(StorageKind::Cache),
+            indexeddb: usage(StorageKind::IndexedDb),
+        };
+        platform::cache_usage(Box::new(move |cache| {
+            callback(cache.map(|cache| DataUsage {
+                cache: usage.cache + cache,
+                ..usage
+            }))
+        }));
+    }
+}
+
+// WebKitGTK keeps one disk cache for every context of the app, outside of
+// their directories. Only the engine knows where, so it's cleared and
+// measured through a data manager, for all partitions at once.
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{CacheUsageCallback, ClearDataCallback};
+    use webkit2gtk::{
+        gio, glib, WebsiteDataManager, WebsiteDataManagerExt, WebsiteDataManagerExtManual,
+        WebsiteDataTypes,
+    };
+
+    pub fn clear_cache(callback: ClearDataCallback) {
+        let manager = WebsiteDataManager::builder().build();
+        let types = WebsiteDataTypes::DISK_CACHE;
+        // a time span of zero clears everything
+        manager.clear(
+            types,
+            glib::TimeSpan(0),
+            None::<&gio::Cancellable>,
+            move |result| {
+                callback(result.map_err(|err| err.to_string()));
+            },
+        );
+    }
+
+    pub fn cache_usage(callback: CacheUsageCallback) {
+        let manager = WebsiteDataManager::builder().build();
+        let types = WebsiteDataTypes::DISK_CACHE;
+        manager.fetch(types, None::<&gio::Cancellable>, move |result| {
+            let result = result
+                .map(|data| data.iter().map(|data| data.size(types)).sum())
+                .map_err(|err| err.to_string());
+            callback(result);
+        });
+    }
+}
+
+// the cache is a part of the data directory, `StorageKind::paths` has it
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::{CacheUsageCallback, ClearDataCallback};
+
+    pub fn clear_cache(callback: ClearDataCallback) {
+        callback(Ok(()));
+    }
+
+    pub fn cache_usage(callback: CacheUsageCallback) {
+        callback(Ok(0));
+    }
+}
+
+fn remove_path(path: &Path) -> io::Result<()> {
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    match result {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+fn path_size(path: &Path) -> u64 {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return 0,
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| path_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+pub fn default_data_dir(app_id: &str) -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(app_id)
+}
@@ -0,0 +1,79 @@
+use crate::zoom::ZoomStep;
+use wry::application::window::Window;
+
+// Window chrome messages sent by the bridge for frameless windows.
+pub enum WindowControl {
+    Drag,
+    ToggleMaximize,
+    // edge like "n" or "se", with the pointer in physical screen coordinates
+    Resize(String, i32, i32),
+    Zoom(ZoomStep),
+    // a page finished loading the bridge and wants the window state
+    BridgeReady,
+}
+
+impl WindowControl {
+    pub fn parse(message: &str) -> Option<Self> {
+        match message {
+            "drag-window" => return Some(WindowControl::Drag),
+            "maximize-window" => return Some(WindowControl::ToggleMaximize),
+            "zoom:in" => return Some(WindowControl::Zoom(ZoomStep::In)),
+            "zoom:out" => return Some(WindowControl::Zoom(ZoomStep::Out)),
+            "zoom:reset" => return Some(WindowControl::Zoom(ZoomStep::Reset)),
+            "bridge-ready" => return Some(WindowControl::BridgeReady),
+            _ => {}
+        }
+        let mut parts = message.strip_prefix("resize-window:")?.split(':');
+        let edge = parts.next()?.to_string();
+        let x = parts.next()?.parse().ok()?;
+        let y = parts.next()?.parse().ok()?;
+        Some(WindowControl::Resize(edge, x, y))
+    }
+
+    // Controls that only touch the window itself, the rest need state kept
+    // by the event loop.
+    pub fn apply(self, window: &Window) {
+        match self {
+            WindowControl::Drag => {
+                let _ = window.drag_window();
+            }
+            WindowControl::ToggleMaximize => {
+                if window.is_resizable() {
+                    window.set_maximized(!window.is_maximized());
+                }
+            }
+            WindowControl::Resize(edge, x, y) => {
+                if !window.is_decorated() && window.is_resizable() {
+                    begin_resize(window, &edge, x, y);
+                }
+            }
+            WindowControl::Zoom(_) | WindowControl::BridgeReady => {}
+        }
+    }
+}
+
+// The bridge only reports edges where wry doesn't resize frameless windows
+// on its own.
+pub const BRIDGE_RESIZE_EDGES: bool = cfg!(target_os = "windows");
+
+#[cfg(target_os = "windows")]
+fn begin_resize(window: &Window, edge: &str, x: i32, y: i32) {
+    use wry::application::platform::windows::WindowExtWindows;
+
+    const WM_NCLBUTTONDOWN: u32 = 0x00A1;
+    let hit_test = match edge {
+        "w" => 10,
+        "e" => 11,
+        "n" => 12,
+        "nw" => 13,
+        "ne" => 14,
+        "s" => 15,
+        "sw" => 16,
+        "se" => 17,
+        _ => return,
+    };
+    window.begin_resize_drag(hit_test, WM_NCLBUTTONDOWN, x, y);
+}
+
+#[cfg(not(target_os = "windows"))]
+fn begin_resize(_window: &Window, _edge: &str, _x: i32, _y: i32) {}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use wry::application::{
+    dpi::LogicalPosition,
+    window::{Window, WindowId},
+};
+
+struct Entry {
+    id: u32,
+    label: Option<String>,
+    // tao has no getter for it
+    title: String,
+    focused: bool,
+}
+
+pub struct WindowInfo {
+    pub id: u32,
+    pub label: Option<String>,
+    pub title: String,
+    pub visible: bool,
+    pub focused: bool,
+    // in css pixels
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+// WindowId can't be compared from Node, windows get a plain number instead.
+// Numbers are never reused, so a stale one can't point at another window.
+pub struct WindowIds {
+    next_id: u32,
+    windows: HashMap<WindowId, Entry>,
+}
+
+impl WindowIds {
+    pub fn new() -> Self {
+        WindowIds {
+            next_id: 1,
+            windows: HashMap::new(),
+        }
+    }
+
+    // labels are unique among open windows
+    pub fn check_label(&self, label: &Option<String>) -> Result<(), String> {
+        match label {
+            Some(label) if self.by_label(label).is_some() => {
+                Err(format!("a window with label \"{}\" is already open", label))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn add(&mut self, window_id: WindowId, label: Option<String>, title: String) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        let entry = Entry {
+            id,
+            label,
+            title,
+            focused: false,
+        };
+        self.windows.insert(window_id, entry);
+        id
+    }
+
+    // `None` once the window is closed, events still queued for it are dropped
+    pub fn get(&self, window_id: &WindowId) -> Option<u32> {
+        self.windows.get(window_id).map(|entry| entry.id)
+    }
+
+    pub fn window_id(&self, id: u32) -> Option<WindowId> {
+        self.windows
+            .iter()
+            .find(|(_, entry)| entry.id == id)
+            .map(|(window_id, _)| *window_id)
+    }
+
+    pub fn by_label(&self, label: &str) -> Option<u32> {
+        self.windows
+            .values()
+            .find(|entry| entry.label.as_deref() == Some(label))
+            .map(|entry| entry.id)
+    }
+
+    pub fn set_title(&mut self, window_id: &WindowId, title: String) {
+        if let Some(entry) = self.windows.get_mut(window_id) {
+            entry.title = title;
+        }
+    }
+
+    pub fn set_focused(&mut self, window_id: &WindowId, focused: bool) {
+        if let Some(entry) = self.windows.get_mut(window_id) {
+            entry.focused = focused;
+        }
+    }
+
+    pub fn info(&self, window_id: &WindowId, window: &Window) -> Option<WindowInfo> {
+        let entry = self.windows.get(window_id)?;
+        let scale_factor = window.scale_factor();
+        let position = window
+            .outer_position()
+            .map(|position| position.to_logical::<f64>(scale_factor))
+            .unwrap_or_else(|_| LogicalPosition::new(0.0, 0.0));
+        let size = window.inner_size().to_logical::<f64>(scale_factor);
+        Some(WindowInfo {
+            id: entry.id,
+            label: entry.label.clone(),
+            title: entry.title.clone(),
+            visible: window.is_visible(),
+            focused: entry.focused,
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        })
+    }
+
+    pub fn release(&mut self, window_id: &WindowId) {
+        self.windows.remove(window_id);
+    }
+}
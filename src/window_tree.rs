@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use wry::{
+    application::window::{Window, WindowId},
+    webview::WebView,
+};
+
+pub use platform::with_parent;
+
+struct Parent {
+    window_id: WindowId,
+    modal: bool,
+}
+
+// Parent links between windows. Children stay above and close along with
+// their parent, modal ones block input to it while they're open.
+pub struct WindowTree {
+    parents: HashMap<WindowId, Parent>,
+}
+
+impl WindowTree {
+    pub fn new() -> Self {
+        WindowTree {
+            parents: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, child: &Window, parent: &Window, modal: bool) {
+        platform::attach(child, parent);
+        if modal {
+            platform::set_blocked(parent, true);
+        }
+        let parent = Parent {
+            window_id: parent.id(),
+            modal,
+        };
+        self.parents.insert(child.id(), parent);
+    }
+
+    // every window below `window_id`, children before their own parents
+    pub fn descendants(&self, window_id: &WindowId) -> Vec<WindowId> {
+        let mut descendants = Vec::new();
+        for (child, parent) in &self.parents {
+            if parent.window_id == *window_id {
+                descendants.extend(self.descendants(child));
+                descendants.push(*child);
+            }
+        }
+        descendants
+    }
+
+    // Forgets a closed window and gives input back to its parent once the
+    // last modal child is gone.
+    pub fn remove(&mut self, window_id: &WindowId, webviews: &HashMap<WindowId, WebView>) {
+        let parent = match self.parents.remove(window_id) {
+            Some(parent) => parent,
+            None => return,
+        };
+        let blocked = self
+            .parents
+            .values()
+            .any(|other| other.modal && other.window_id == parent.window_id);
+        if parent.modal && !blocked {
+            if let Some(webview) = webviews.get(&parent.window_id) {
+                platform::set_blocked(webview.window(), false);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use gtk::prelude::*;
+    use wry::application::{
+        platform::unix::WindowExtUnix,
+        window::{Window, WindowBuilder},
+    };
+
+    // windows are attached once they're built
+    pub fn with_parent(
+        builder: WindowBuilder,
+        _parent: Option<&Window>,
+        _modal: bool,
+    ) -> Result<WindowBuilder, String> {
+        Ok(builder)
+    }
+
+    pub fn attach(child: &Window, parent: &Window) {
+        child
+            .gtk_window()
+            .set_transient_for(Some(parent.gtk_window()));
+    }
+
+    // an insensitive window keeps drawing but takes no input
+    pub fn set_blocked(window: &Window, blocked: bool) {
+        window.gtk_window().set_sensitive(!blocked);
+    }
+}
+
+// The owner can only be given while building the window, it keeps the child
+// above and minimizes along with it.
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::Win32::Foundation::HWND;
+    use wry::application::{
+        platform::windows::{WindowBuilderExtWindows, WindowExtWindows},
+        window::{Window, WindowBuilder},
+    };
+
+    pub fn with_parent(
+        builder: WindowBuilder,
+        parent: Option<&Window>,
+        _modal: bool,
+    ) -> Result<WindowBuilder, String> {
+        Ok(match parent {
+            Some(parent) => builder.with_owner_window(HWND(parent.hwnd() as _)),
+            None => builder,
+        })
+    }
+
+    pub fn attach(_child: &Window, _parent: &Window) {}
+
+    // a disabled window takes no input, the parent has to be enabled again
+    // before its modal child closes or another app gets the focus
+    pub fn set_blocked(window: &Window, blocked: bool) {
+        window.set_enable(!blocked);
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+mod platform {
+    use wry::application::window::{Window, WindowBuilder};
+
+    pub fn with_parent(
+        builder: WindowBuilder,
+        parent: Option<&Window>,
+        modal: bool,
+    ) -> Result<WindowBuilder, String> {
+        if parent.is_some() || modal {
+            return Err("parent and modal windows are not supported on this platform".to_string());
+        }
+        Ok(builder)
+    }
+
+    pub fn attach(_child: &Window, _parent: &Window) {}
+
+    pub fn set_blocked(_window: &Window, _blocked: bool) {}
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use wry::{application::window::WindowId, webview::WebView};
+
+// WKWebView's page zoom needs macOS 11, pages are scaled with CSS there.
+pub const NATIVE: bool = cfg!(not(target_os = "macos"));
+
+const MIN: f64 = 0.25;
+const MAX: f64 = 5.0;
+// the steps browsers use for their zoom hotkeys
+const STEPS: &[f64] = &[
+    0.25, 0.33, 0.5, 0.67, 0.75, 0.8, 0.9, 1.0, 1.1, 1.25, 1.5, 1.75, 2.0, 2.5, 3.0, 4.0, 5.0,
+];
+
+#[derive(Clone, Copy)]
+pub enum ZoomStep {
+    In,
+    Out,
+    Reset,
+}
+
+pub struct Zooms {
+    levels: HashMap<WindowId, f64>,
+}
+
+impl Zooms {
+    pub fn new() -> Self {
+        Zooms {
+            levels: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, window_id: &WindowId) -> f64 {
+        self.levels.get(window_id).copied().unwrap_or(1.0)
+    }
+
+    // Returns the factor actually applied, it's clamped to what engines allow.
+    pub fn set(&mut self, webview: &WebView, window_id: WindowId, factor: f64) -> f64 {
+        let factor = if factor.is_finite() {
+            factor.clamp(MIN, MAX)
+        } else {
+            1.0
+        };
+        self.levels.insert(window_id, factor);
+        if NATIVE {
+            webview.zoom(factor);
+        } else {
+            let _ = webview.evaluate_script(&css_zoom_script(factor));
+        }
+        factor
+    }
+
+    pub fn step(&mut self, webview: &WebView, window_id: WindowId, step: ZoomStep) -> f64 {
+        let current = self.get(&window_id);
+        let factor = match step {
+            ZoomStep::In => STEPS
+                .iter()
+                .copied()
+                .find(|factor| *factor > current + f64::EPSILON)
+                .unwrap_or(MAX),
+            ZoomStep::Out => STEPS
+                .iter()
+                .rev()
+                .copied()
+                .find(|factor| *factor < current - f64::EPSILON)
+                .unwrap_or(MIN),
+            ZoomStep::Reset => 1.0,
+        };
+        self.set(webview, window_id, factor)
+    }
+
+    // the CSS zoom has to be applied again on every page
+    pub fn css_zoom(&self, window_id: &WindowId) -> Option<f64> {
+        if NATIVE {
+            None
+        } else {
+            Some(self.get(window_id))
+        }
+    }
+
+    pub fn release(&mut self, window_id: &WindowId) {
+        self.levels.remove(window_id);
+    }
+}
+
+fn css_zoom_script(factor: f64) -> String {
+    format!(
+        "document.documentElement && (document.documentElement.style.zoom = \"{}\")",
+        factor
+    )
+}
@@ -12,10 +12,40 @@ async function main() {
     let height = 600;
     let visible = true;
     let resizable = true;
-    let initialization_script = "console.log('test')";
+    let initialization_scripts = [
+        { source: "console.log('test')", allFrames: false },
+    ];
+    let file_drop = false;
+    let file_drop_prevent_default = false;
+    let downloads = false;
+    let partition = null;
+    let ephemeral = true;
+    let ipc_origins = null;
+    let remote_ipc = true;
+    let file_ipc = false;
+    let csp = null;
+    let assets_dir = null;
+    let allowed_channels = null;
+    let denied_channels = [];
+    let ipc_rate_limit = null;
+    let zoom = 1;
+    let zoom_hotkeys = false;
+    let parent = null;
+    let modal = false;
+    let label = null;
+    let theme = "system";
+
+    let app_id = "webarea-test";
+    let data_dir = null;
+    let devtools_allowed = true;
+    let headless = false;
+    let url_schemes = [];
 
     const listener = console.log;
-    lib.app_init(listener, (ipcBoxed) => {
+    const onReady = (error, ipcBoxed) => {
+        if (error) {
+            throw new Error(error);
+        }
         console.log(ipcBoxed);
         for (let i = 0; i < 1; i++) {
             lib.create_new_window(
@@ -28,7 +58,26 @@ async function main() {
                 height,
                 visible,
                 resizable,
-                initialization_script,
+                initialization_scripts,
+                file_drop,
+                file_drop_prevent_default,
+                downloads,
+                partition,
+                ephemeral,
+                ipc_origins,
+                remote_ipc,
+                file_ipc,
+                csp,
+                assets_dir,
+                allowed_channels,
+                denied_channels,
+                ipc_rate_limit,
+                zoom,
+                zoom_hotkeys,
+                parent,
+                modal,
+                label,
+                theme,
                 (windowIdBoxed) => {
                     console.log("created");
                     console.log(windowIdBoxed);
@@ -44,7 +93,16 @@ async function main() {
                 }
             );
         }
-    });
+    };
+    lib.app_init(
+        listener,
+        onReady,
+        app_id,
+        data_dir,
+        devtools_allowed,
+        headless,
+        url_schemes
+    );
 }
 
 main();